@@ -0,0 +1,263 @@
+use std::fs;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use sha2::Digest;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+use crate::errors::CarboneError;
+
+use crate::types::Result;
+
+// entries written first, ODF requires its mimetype to be the first entry
+const LEADING_ENTRIES: [&str; 2] = ["mimetype", "[Content_Types].xml"];
+
+// version control and OS files which are not part of a template,
+// other dot-files are kept, OOXML requires `_rels/.rels`
+const IGNORED_FILES: [&str; 10] = [
+    ".git",
+    ".gitattributes",
+    ".gitignore",
+    ".gitkeep",
+    ".hg",
+    ".svn",
+    ".DS_Store",
+    ".directory",
+    "Thumbs.db",
+    "desktop.ini",
+];
+
+fn zip_error(e: ZipError) -> CarboneError {
+    match e {
+        ZipError::Io(e) => CarboneError::IoError(e),
+        e => CarboneError::InvalidTemplate(e.to_string()),
+    }
+}
+
+/// Zip the files of an unpacked template, the same files always give the same bytes:
+/// the entries are sorted, their timestamps and permissions fixed, and `mimetype`
+/// is written first and uncompressed. Version control and OS files (e.g. `.gitkeep`,
+/// `.DS_Store`) are left out.
+pub(crate) fn zip_dir(dir: &Path) -> Result<Vec<u8>> {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    walk(dir, dir, &mut files, &mut dirs)?;
+
+    files.sort_by_key(|name| {
+        let leading = LEADING_ENTRIES.iter().position(|entry| entry == name);
+        (leading.unwrap_or(LEADING_ENTRIES.len()), name.clone())
+    });
+    dirs.sort();
+
+    let options = FileOptions::default()
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for name in &files {
+        let options = match name.as_str() {
+            "mimetype" => options.compression_method(CompressionMethod::Stored),
+            _ => options.compression_method(CompressionMethod::Deflated),
+        };
+
+        writer.start_file(name, options).map_err(zip_error)?;
+        writer.write_all(&fs::read(dir.join(name))?)?;
+    }
+
+    // only the empty directories need an entry of their own
+    for name in &dirs {
+        let prefix = format!("{}/", name);
+        if !files.iter().any(|file| file.starts_with(&prefix)) {
+            writer
+                .add_directory(name, options.unix_permissions(0o755))
+                .map_err(zip_error)?;
+        }
+    }
+
+    Ok(writer.finish().map_err(zip_error)?.into_inner())
+}
+
+/// Collect the files and directories under `dir`, as `/` separated paths relative to `root`.
+fn walk(root: &Path, dir: &Path, files: &mut Vec<String>, dirs: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        let ignored = path
+            .file_name()
+            .map(|name| {
+                let name = name.to_string_lossy();
+                // `._*` are the resource forks written by macOS on foreign file systems
+                IGNORED_FILES.contains(&name.as_ref()) || name.starts_with("._")
+            })
+            .unwrap_or(true);
+
+        if ignored {
+            continue;
+        }
+
+        let name = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if path.is_dir() {
+            walk(root, &path, files, dirs)?;
+            dirs.push(name);
+        } else {
+            files.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract a zipped template into `dir`, refusing entries which would land outside of it.
+pub(crate) fn unzip_to_dir(content: &[u8], dir: &Path) -> Result<()> {
+    let mut archive = ZipArchive::new(Cursor::new(content)).map_err(zip_error)?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(zip_error)?;
+
+        let name: PathBuf = match entry.enclosed_name() {
+            Some(name) => name.to_owned(),
+            None => {
+                return Err(CarboneError::InvalidTemplate(format!(
+                    "unsafe entry name {:?}",
+                    entry.name()
+                )))
+            }
+        };
+
+        let path = dir.join(name);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        io::copy(&mut entry, &mut fs::File::create(&path)?)?;
+    }
+
+    Ok(())
+}
+
+// entries rewritten by office suites on every save, whatever changed in the document
+const VOLATILE_ENTRIES: [&str; 4] = [
+    "settings.xml",
+    "Thumbnails/",
+    "docProps/thumbnail.",
+    "docProps/app.xml",
+];
+
+// metadata updated on every save, e.g. the date and the number of editing cycles
+const VOLATILE_ELEMENTS: [&str; 10] = [
+    "meta:generator",
+    "dc:date",
+    "meta:editing-cycles",
+    "meta:editing-duration",
+    "meta:print-date",
+    "meta:printed-by",
+    "dcterms:modified",
+    "cp:lastModifiedBy",
+    "cp:revision",
+    "cp:lastPrinted",
+];
+
+/// Feed a zipped template to the hasher independently of its packaging: the entries
+/// are sorted by name and hashed decompressed, the directory entries and the volatile
+/// entries are skipped, line endings and volatile metadata are removed from the XML.
+pub(crate) fn canonical_hash<D: Digest>(content: &[u8], hasher: &mut D) -> Result<()> {
+    let mut archive = ZipArchive::new(Cursor::new(content)).map_err(zip_error)?;
+
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .filter(|name| !VOLATILE_ENTRIES.iter().any(|entry| name.starts_with(entry)))
+        .map(String::from)
+        .collect();
+    names.sort();
+
+    for name in names {
+        let mut data = Vec::new();
+        archive
+            .by_name(&name)
+            .map_err(zip_error)?
+            .read_to_end(&mut data)?;
+
+        let is_xml = [".xml", ".rels", ".rdf"]
+            .iter()
+            .any(|extension| name.ends_with(extension));
+
+        if is_xml {
+            if let Ok(xml) = String::from_utf8(data.clone()) {
+                data = normalize_xml(&xml).into_bytes();
+            }
+        }
+
+        hasher.update(name.as_bytes());
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(&data);
+    }
+
+    Ok(())
+}
+
+fn normalize_xml(xml: &str) -> String {
+    let mut xml = xml.replace("\r\n", "\n");
+
+    for tag in VOLATILE_ELEMENTS {
+        xml = remove_elements(&xml, tag);
+    }
+
+    xml
+}
+
+/// Remove the `<tag>...</tag>` and `<tag/>` elements of an XML document.
+fn remove_elements(xml: &str, tag: &str) -> String {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut result = String::with_capacity(xml.len());
+    let mut rest = xml;
+
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+
+        // another element sharing the prefix of the tag, e.g. <meta:generator-x>
+        if !after.starts_with([' ', '>', '/', '\n', '\t']) {
+            result.push_str(&rest[..start + open.len()]);
+            rest = after;
+            continue;
+        }
+
+        result.push_str(&rest[..start]);
+
+        let end = match after.find('>') {
+            Some(i) if after[..i].ends_with('/') => Some(i + 1),
+            Some(_) => after.find(&close).map(|i| i + close.len()),
+            None => None,
+        };
+
+        match end {
+            Some(end) => rest = &after[end..],
+            None => {
+                // malformed, keep the rest as is
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,314 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+
+use crate::errors::{CarboneError, ConfigError};
+use crate::types::ApiJsonToken;
+
+use crate::types::Result;
+
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = Result<ApiJsonToken>> + Send + 'a>>;
+
+/// A source of API tokens, asked for a token before each request
+/// so that a rotated token is used without rebuilding the client.
+pub trait TokenProvider: Send + Sync + fmt::Debug {
+    fn token(&self) -> TokenFuture<'_>;
+}
+
+/// How the requests sent to the Carbone API are authenticated.
+///
+/// Carbone Cloud needs a bearer token, self-hosted Carbone On-Premise
+/// instances often run without authentication or behind a proxy
+/// expecting its own header.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rs::auth::Auth;
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::types::ApiVersion;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let api_version = ApiVersion::new("4".to_string())?;
+///     let config = Config::new("http://localhost:4000".to_string(), 60, api_version)?;
+///
+///     let carbone = Carbone::with_auth(&config, Auth::None)?;
+///
+///     // or behind a proxy
+///     let auth = Auth::header("X-Api-Key", "secret")?;
+///     let carbone = Carbone::with_auth(&config, auth)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// `Authorization: Bearer <token>`, the token being asked to the provider before each request.
+    Bearer(Arc<dyn TokenProvider>),
+    /// No authentication header.
+    None,
+    /// A custom header, sent as is.
+    Header {
+        name: HeaderName,
+        value: HeaderValue,
+    },
+}
+
+impl Auth {
+    /// Bearer authentication with always the same token.
+    pub fn bearer(api_token: ApiJsonToken) -> Self {
+        Self::Bearer(Arc::new(StaticToken::new(api_token)))
+    }
+
+    /// Authentication with a custom header, the value is marked as sensitive.
+    pub fn header(name: &str, value: &str) -> Result<Self> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| ConfigError::InvalidValue("authHeader".to_string(), name.to_string()))?;
+
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| ConfigError::InvalidValue(name.to_string(), "[REDACTED]".to_string()))?;
+        value.set_sensitive(true);
+
+        Ok(Self::Header { name, value })
+    }
+
+    /// The header to add to a request, if any.
+    pub(crate) async fn resolve(&self) -> Result<Option<(HeaderName, HeaderValue)>> {
+        match self {
+            Self::Bearer(token_provider) => {
+                let api_token = token_provider.token().await?;
+
+                let bearer = format!("Bearer {}", api_token.as_str());
+
+                let mut value = HeaderValue::from_str(bearer.as_str())
+                    .map_err(|e| CarboneError::InvalidToken(e.to_string()))?;
+                value.set_sensitive(true);
+
+                Ok(Some((AUTHORIZATION, value)))
+            }
+            Self::None => Ok(None),
+            Self::Header { name, value } => Ok(Some((name.clone(), value.clone()))),
+        }
+    }
+}
+
+/// Always the same token.
+#[derive(Debug, Clone)]
+pub struct StaticToken(ApiJsonToken);
+
+impl StaticToken {
+    pub fn new(api_token: ApiJsonToken) -> Self {
+        Self(api_token)
+    }
+}
+
+impl TokenProvider for StaticToken {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move { Ok(self.0.clone()) })
+    }
+}
+
+/// The token read from an environment variable, `CARBONE_TOKEN` by default.
+#[derive(Debug, Clone)]
+pub struct EnvToken {
+    var: String,
+}
+
+impl EnvToken {
+    pub fn new<T: Into<String>>(var: T) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl Default for EnvToken {
+    fn default() -> Self {
+        Self::new(crate::config::ENV_TOKEN)
+    }
+}
+
+impl TokenProvider for EnvToken {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move {
+            match env::var(&self.var) {
+                Ok(v) => ApiJsonToken::new(v),
+                Err(_) => Err(ConfigError::MissingField(self.var.to_string()).into()),
+            }
+        })
+    }
+}
+
+/// The token read from a file, which is read again when the file is modified.
+#[derive(Debug)]
+pub struct FileToken {
+    path: PathBuf,
+    cached: Mutex<Option<(SystemTime, ApiJsonToken)>>,
+}
+
+impl FileToken {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    fn read(&self) -> Result<ApiJsonToken> {
+        let modified = fs::metadata(&self.path)?.modified()?;
+
+        let mut cached = self.cached.lock().unwrap();
+
+        if let Some((cached_modified, api_token)) = cached.as_ref() {
+            if *cached_modified == modified {
+                return Ok(api_token.clone());
+            }
+        }
+
+        let api_token = ApiJsonToken::new(fs::read_to_string(&self.path)?.trim().to_string())?;
+        *cached = Some((modified, api_token.clone()));
+
+        Ok(api_token)
+    }
+}
+
+impl TokenProvider for FileToken {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move { self.read() })
+    }
+}
+
+/// The token returned by an async closure, e.g. fetched from a secret store.
+///
+/// Wrap it in a [`CachedTokenProvider`] to not call the closure before each request.
+pub struct FnTokenProvider<F> {
+    f: F,
+}
+
+impl<F, Fut> FnTokenProvider<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<ApiJsonToken>> + Send + 'static,
+{
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F> fmt::Debug for FnTokenProvider<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnTokenProvider").finish_non_exhaustive()
+    }
+}
+
+impl<F, Fut> TokenProvider for FnTokenProvider<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<ApiJsonToken>> + Send + 'static,
+{
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin((self.f)())
+    }
+}
+
+/// Cache the token of another provider until it expires,
+/// or until it is older than `max_age` if one is set.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::env;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use carbone_sdk_rs::auth::{CachedTokenProvider, FnTokenProvider};
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::types::ApiJsonToken;
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///
+///     let provider = FnTokenProvider::new(|| async {
+///         // e.g. fetch the token from a secret store
+///         ApiJsonToken::new(env::var("CARBONE_TOKEN").unwrap())
+///     });
+///     let provider = CachedTokenProvider::new(provider).max_age(Duration::from_secs(300));
+///
+///     let carbone = Carbone::with_token_provider(&config, Arc::new(provider))?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CachedTokenProvider<P> {
+    inner: P,
+    max_age: Option<Duration>,
+    cached: Mutex<Option<(Instant, ApiJsonToken)>>,
+}
+
+impl<P: TokenProvider> CachedTokenProvider<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            max_age: None,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    fn cached(&self) -> Option<ApiJsonToken> {
+        let cached = self.cached.lock().unwrap();
+
+        match cached.as_ref() {
+            Some((resolved_at, api_token)) => {
+                let too_old = self
+                    .max_age
+                    .map(|max_age| resolved_at.elapsed() >= max_age)
+                    .unwrap_or(false);
+
+                if too_old || api_token.is_expired() {
+                    None
+                } else {
+                    Some(api_token.clone())
+                }
+            }
+            None => None,
+        }
+    }
+}
+
+impl<P: TokenProvider> TokenProvider for CachedTokenProvider<P> {
+    fn token(&self) -> TokenFuture<'_> {
+        Box::pin(async move {
+            if let Some(api_token) = self.cached() {
+                return Ok(api_token);
+            }
+
+            let api_token = self.inner.token().await?;
+            *self.cached.lock().unwrap() = Some((Instant::now(), api_token.clone()));
+
+            Ok(api_token)
+        })
+    }
+}
+
+impl<T: TokenProvider + ?Sized> TokenProvider for Arc<T> {
+    fn token(&self) -> TokenFuture<'_> {
+        (**self).token()
+    }
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+
+use carbone_sdk_rs::auth::Auth;
+use carbone_sdk_rs::blocking::Carbone;
+use carbone_sdk_rs::config::{Config, ConfigLoader, PartialConfig};
+use carbone_sdk_rs::errors::{CarboneError, ConfigError};
+use carbone_sdk_rs::i18n::{Catalog, Translations};
+use carbone_sdk_rs::status::VersionCheck;
+use carbone_sdk_rs::template::{self, TemplateFile, TemplateId};
+use carbone_sdk_rs::types::{ApiJsonToken, JsonData, Result};
+
+// exit codes from sysexits.h
+const EX_DATAERR: u8 = 65;
+const EX_NOINPUT: u8 = 66;
+const EX_UNAVAILABLE: u8 = 69;
+const EX_SOFTWARE: u8 = 70;
+const EX_CANTCREAT: u8 = 73;
+const EX_IOERR: u8 = 74;
+const EX_CONFIG: u8 = 78;
+
+/// Command-line client for the Carbone API.
+#[derive(Debug, Parser)]
+#[command(name = "carbone", version, about)]
+struct Cli {
+    /// JSON, TOML or YAML configuration file, overridden by the CARBONE_* environment variables.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// API token used to authenticate against the Carbone API, instead of CARBONE_TOKEN.
+    #[arg(long, global = true)]
+    token: Option<String>,
+
+    /// Send the requests without authentication, e.g. to a Carbone On-Premise instance.
+    #[arg(long, global = true, conflicts_with = "token")]
+    no_auth: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Upload a template and print its template id.
+    Upload {
+        template: PathBuf,
+        #[arg(long)]
+        salt: Option<String>,
+    },
+    /// Download a template.
+    Download {
+        template_id: String,
+        output: PathBuf,
+    },
+    /// Delete a template.
+    Delete { template_id: String },
+    /// Render a report from a template (path or template id) and a JSON data file.
+    Render {
+        template: String,
+        data: PathBuf,
+        output: PathBuf,
+        #[arg(long)]
+        convert_to: Option<String>,
+        /// Language of the report, e.g. fr-fr.
+        #[arg(long)]
+        lang: Option<String>,
+        /// Directory of the .po and .json catalogs translating the {t(...)} markers.
+        #[arg(long)]
+        translations: Option<PathBuf>,
+    },
+    /// Compute the template id of a template locally.
+    Id {
+        template: PathBuf,
+        #[arg(long)]
+        payload: Option<String>,
+    },
+    /// List the data and complement markers of a template.
+    Markers { template: PathBuf },
+    /// Extract the {t(...)} texts of a template into a PO catalog.
+    Catalog {
+        template: PathBuf,
+        /// Language of the catalog, e.g. fr-fr.
+        #[arg(long)]
+        lang: Option<String>,
+        /// Previous catalog whose translations are kept.
+        #[arg(long)]
+        merge: Option<PathBuf>,
+        /// Print the catalog as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare the markers, loops and conditions of two versions of a template.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Print the differences as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Extract a template into a directory, e.g. to keep its XML files in git.
+    Unpack { template: PathBuf, output: PathBuf },
+    /// Print the status and the version of the Carbone service.
+    Status {
+        /// Fail when the server does not support the configured api_version.
+        #[arg(long)]
+        check_version: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let mut loader = ConfigLoader::new().env().overrides(PartialConfig {
+        api_token: cli.token.map(ApiJsonToken::new).transpose()?,
+        ..Default::default()
+    });
+
+    if let Some(path) = cli.config {
+        loader = loader.file(path);
+    }
+
+    let config = loader.load()?;
+
+    match cli.command {
+        Command::Upload { template, salt } => {
+            let carbone = Carbone::with_auth(&config, auth(&config, cli.no_auth)?)?;
+            let template_file = template_file(&template)?;
+            let template_id = carbone.upload_template(&template_file, salt.as_deref())?;
+            println!("{}", template_id.as_str());
+        }
+        Command::Download {
+            template_id,
+            output,
+        } => {
+            let carbone = Carbone::with_auth(&config, auth(&config, cli.no_auth)?)?;
+            let content = carbone.download_template(&TemplateId::new(template_id)?)?;
+            fs::write(output, content)?;
+        }
+        Command::Delete { template_id } => {
+            let carbone = Carbone::with_auth(&config, auth(&config, cli.no_auth)?)?;
+            carbone.delete_template(TemplateId::new(template_id)?)?;
+        }
+        Command::Render {
+            template,
+            data,
+            output,
+            convert_to,
+            lang,
+            translations,
+        } => {
+            let carbone = Carbone::with_auth(&config, auth(&config, cli.no_auth)?)?;
+            let mut json_data = json_data(&data, convert_to, lang)?;
+
+            if let Some(dir) = translations {
+                json_data = Translations::load_dir(dir)?
+                    .strict()
+                    .apply(&json_data, None)?;
+            }
+
+            let report_content = if Path::new(&template).exists() {
+                let template_file = template_file(Path::new(&template))?;
+                carbone.generate_report_with_file(&template_file, json_data, None)?
+            } else {
+                let template_id = TemplateId::new(template)?;
+                carbone.generate_report_with_template_id(template_id, json_data)?
+            };
+
+            fs::write(output, report_content)?;
+        }
+        Command::Id { template, payload } => {
+            let template_id = template_file(&template)?.generate_id(payload.as_deref())?;
+            println!("{}", template_id.as_str());
+        }
+        Command::Markers { template } => {
+            for marker in template_file(&template)?.markers()? {
+                println!("{}\t{{{}}}", marker.location.entry, marker.raw);
+            }
+        }
+        Command::Catalog {
+            template,
+            lang,
+            merge,
+            json,
+        } => {
+            let mut catalog = Catalog::extract(&template_file(&template)?)?;
+            catalog.lang = lang;
+
+            if let Some(previous) = merge {
+                catalog.merge(&Catalog::load(previous)?);
+            }
+
+            if json {
+                println!("{:#}", catalog.to_json());
+            } else {
+                print!("{}", catalog.to_po());
+            }
+        }
+        Command::Diff { old, new, json } => {
+            let diff = template::diff(&template_file(&old)?, &template_file(&new)?)?;
+
+            if json {
+                println!("{:#}", serde_json::json!(diff));
+            } else {
+                print!("{}", diff);
+            }
+        }
+        Command::Unpack { template, output } => {
+            template_file(&template)?.unpack_to_dir(output)?;
+        }
+        Command::Status { check_version } => {
+            let carbone = Carbone::with_auth(&config, auth(&config, cli.no_auth)?)?;
+
+            let status = if check_version {
+                carbone.negotiate_version(VersionCheck::Fail)?
+            } else {
+                carbone.status()?
+            };
+
+            println!(
+                "{}\t{}",
+                status.message.as_deref().unwrap_or("-"),
+                status.version.as_deref().unwrap_or("-")
+            );
+
+            if !status.is_healthy() {
+                return Err(CarboneError::ServerError);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn auth(config: &Config, no_auth: bool) -> Result<Auth> {
+    if no_auth {
+        return Ok(Auth::None);
+    }
+
+    match &config.api_token {
+        Some(token) => Ok(Auth::bearer(token.clone())),
+        None => Err(ConfigError::MissingField("apiToken".to_string()).into()),
+    }
+}
+
+/// Read a template file, or zip the directory of an unpacked template.
+fn template_file(path: &Path) -> Result<TemplateFile> {
+    if path.is_dir() {
+        return TemplateFile::from_unpacked_dir(path);
+    }
+
+    TemplateFile::new(path.to_string_lossy().into_owned(), None)
+}
+
+/// Read the render body from a JSON file and set its `convertTo` and `lang` if given.
+fn json_data(path: &Path, convert_to: Option<String>, lang: Option<String>) -> Result<JsonData> {
+    let content =
+        fs::read_to_string(path).or(Err(CarboneError::FileNotFound(path.display().to_string())))?;
+
+    let mut body: Value = serde_json::from_str(&content)
+        .map_err(|e| CarboneError::ParseError(path.display().to_string(), e.to_string()))?;
+
+    let overrides = [("convertTo", convert_to), ("lang", lang)];
+
+    for (key, value) in overrides {
+        if let Some(value) = value {
+            match body.as_object_mut() {
+                Some(body) => {
+                    body.insert(key.to_string(), Value::String(value));
+                }
+                None => return Err(CarboneError::RequestBodyNotWellFormedJsonError),
+            }
+        }
+    }
+
+    JsonData::new(body.to_string())
+}
+
+fn exit_code(error: &CarboneError) -> u8 {
+    match error {
+        CarboneError::EmptyString(_)
+        | CarboneError::ParseError(_, _)
+        | CarboneError::RequestBodyNotWellFormedJsonError
+        | CarboneError::InvalidTemplate(_) => EX_DATAERR,
+        CarboneError::FileNotFound(_)
+        | CarboneError::TemplateFileNotFound(_)
+        | CarboneError::TranslationsNotFound(_)
+        | CarboneError::IsADirectory(_)
+        | CarboneError::TemplateIdNotFound(_)
+        | CarboneError::RenderIdNotFound(_) => EX_NOINPUT,
+        CarboneError::ConfigError(_) | CarboneError::UnsupportedApiVersion { .. } => EX_CONFIG,
+        CarboneError::RequestError(e) if e.is_builder() => EX_CONFIG,
+        CarboneError::RequestError(_)
+        | CarboneError::ResponseError(_)
+        | CarboneError::ServerError
+        | CarboneError::CircuitOpen => EX_UNAVAILABLE,
+        CarboneError::FileAlreadyExists(_) => EX_CANTCREAT,
+        CarboneError::IoError(_) => EX_IOERR,
+        _ => EX_SOFTWARE,
+    }
+}
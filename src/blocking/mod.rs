@@ -1,53 +1,329 @@
 use bytes::Bytes;
 
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use reqwest::blocking::multipart;
 use reqwest::blocking::Client;
 use reqwest::blocking::ClientBuilder;
+use reqwest::blocking::Request;
+use reqwest::blocking::RequestBuilder;
+use reqwest::blocking::Response;
 use reqwest::header;
-use reqwest::header::HeaderValue;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Method;
 use reqwest::StatusCode;
 
+use tokio::runtime::{Builder, Runtime};
+
+use crate::auth::{Auth, TokenProvider};
+use crate::cache::{CacheKey, ReportCache};
 use crate::carbone_response::APIResponse;
+use crate::circuit_breaker::{CircuitBreaker, CircuitState};
 use crate::config::Config;
+use crate::endpoint::{Endpoint, EndpointPool};
 use crate::errors::*;
+use crate::i18n::Translations;
+use crate::instrumentation::{Call, Operation};
+use crate::middleware::{Middleware, ResponseMeta};
+use crate::options::CallOptions;
+use crate::rate_limit::{Budget, RateLimiter};
 use crate::render::*;
+use crate::report::{data_hash, OnConflict, Report};
+use crate::sniff::TemplateFormat;
+use crate::status::{ServiceStatus, VersionCheck};
 use crate::template::*;
 use crate::types::{ApiJsonToken, JsonData};
 
 use crate::types::Result;
 
+// requests answered with 429 Too Many Requests sent again
+const MAX_RATE_LIMITED_RETRIES: u32 = 2;
+
 #[derive(Debug, Clone)]
 pub struct Carbone<'a> {
     config: &'a Config,
     http_client: Client,
+    auth: Auth,
+    endpoints: Arc<EndpointPool>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    report_cache: Option<Arc<dyn ReportCache>>,
+    translations: Option<Arc<Translations>>,
+    // major version of the server once negotiated, 0 when unknown
+    server_api_version: Arc<AtomicU32>,
+    // runs the token provider futures
+    runtime: Arc<Runtime>,
 }
 
 impl<'a> Carbone<'a> {
     pub fn new(config: &'a Config, api_token: &'a ApiJsonToken) -> Result<Self> {
-        let mut headers = header::HeaderMap::new();
-        headers.insert(
-            "carbone-version",
-            HeaderValue::from_str(config.api_version.as_str()).unwrap(),
-        );
+        Self::with_auth(config, Auth::bearer(api_token.clone()))
+    }
 
-        let bearer = format!("Bearer {}", api_token.as_str());
+    /// Create a new Carbone client which asks the token provider
+    /// for the API token before each request.
+    pub fn with_token_provider(
+        config: &'a Config,
+        token_provider: Arc<dyn TokenProvider>,
+    ) -> Result<Self> {
+        Self::with_auth(config, Auth::Bearer(token_provider))
+    }
 
-        let mut auth_value = header::HeaderValue::from_str(bearer.as_str()).unwrap();
-        auth_value.set_sensitive(true);
+    /// Create a new Carbone client authenticating its requests with `auth`,
+    /// e.g. `Auth::None` for a Carbone On-Premise instance without authentication.
+    pub fn with_auth(config: &'a Config, auth: Auth) -> Result<Self> {
+        Self::builder(config).auth(auth).build()
+    }
 
-        headers.insert(header::AUTHORIZATION, auth_value);
+    /// Start building a Carbone client, see [`CarboneBuilder`].
+    pub fn builder(config: &'a Config) -> CarboneBuilder<'a> {
+        CarboneBuilder::new(config)
+    }
 
-        let http_client = ClientBuilder::new()
-            .default_headers(headers)
-            .timeout(Duration::from_secs(config.api_timeout))
-            .build()?;
+    pub fn config(&self) -> &Config {
+        self.config
+    }
 
-        Ok(Self {
-            config,
-            http_client,
-        })
+    /// The circuit breaker of the client, if enabled in the config.
+    pub fn circuit_breaker(&self) -> Option<Arc<CircuitBreaker>> {
+        self.circuit_breaker.clone()
+    }
+
+    /// The state of the circuit breaker, always closed when it is disabled.
+    pub fn circuit_state(&self) -> CircuitState {
+        match &self.circuit_breaker {
+            Some(circuit_breaker) => circuit_breaker.state(),
+            None => CircuitState::Closed,
+        }
+    }
+
+    pub fn server_api_version(&self) -> Option<u32> {
+        match self.server_api_version.load(Ordering::Relaxed) {
+            0 => None,
+            version => Some(version),
+        }
+    }
+
+    fn send<F>(&self, call: Call, options: &CallOptions, build: F) -> Result<Response>
+    where
+        F: Fn(&str) -> Result<RequestBuilder>,
+    {
+        let (_, response) = self.send_to(call, self.endpoints.candidates(), options, build)?;
+        Ok(response)
+    }
+
+    /// Send the request and record the metrics of the call.
+    fn send_to<F>(
+        &self,
+        call: Call,
+        candidates: Vec<usize>,
+        options: &CallOptions,
+        build: F,
+    ) -> Result<(usize, Response)>
+    where
+        F: Fn(&str) -> Result<RequestBuilder>,
+    {
+        let started_at = Instant::now();
+
+        let outcome = self.send_to_limited(call.operation.budget(), candidates, options, build);
+
+        match &outcome {
+            Ok((_, response)) => call.record(started_at.elapsed(), Some(response.status()), None),
+            Err(e) => call.record(started_at.elapsed(), None, Some(e)),
+        }
+
+        outcome
+    }
+
+    /// Send the request built for the api url of the first candidate endpoint,
+    /// failing over to the next ones as described in `send_to_endpoints`.
+    ///
+    /// Waits for a permit of the rate limiter, and sends the request again when answered
+    /// with `429 Too Many Requests`. Fails fast with `CarboneError::CircuitOpen`
+    /// while the circuit breaker is open.
+    fn send_to_limited<F>(
+        &self,
+        budget: Budget,
+        candidates: Vec<usize>,
+        options: &CallOptions,
+        build: F,
+    ) -> Result<(usize, Response)>
+    where
+        F: Fn(&str) -> Result<RequestBuilder>,
+    {
+        let auth_header = self.runtime.block_on(self.auth.resolve())?;
+
+        let mut retries = 0;
+
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                let wait = rate_limiter.reserve(budget);
+                if !wait.is_zero() {
+                    thread::sleep(wait);
+                }
+            }
+
+            let permit = match &self.circuit_breaker {
+                Some(circuit_breaker) => Some(circuit_breaker.acquire()?),
+                None => None,
+            };
+
+            let outcome =
+                self.send_to_endpoints(candidates.clone(), auth_header.clone(), options, &build);
+
+            if let Some(permit) = permit {
+                match &outcome {
+                    Ok((_, response)) if response.status().is_server_error() => {
+                        permit.record_failure()
+                    }
+                    Err(CarboneError::RequestError(e)) if e.is_connect() || e.is_timeout() => {
+                        permit.record_failure()
+                    }
+                    Ok(_) => permit.record_success(),
+                    // the probe is given back
+                    Err(_) => drop(permit),
+                }
+            }
+
+            if let (Some(rate_limiter), Ok((_, response))) = (&self.rate_limiter, &outcome) {
+                let too_many_requests = response.status() == StatusCode::TOO_MANY_REQUESTS;
+
+                rate_limiter.update(budget, response.headers(), too_many_requests);
+
+                if too_many_requests && retries < MAX_RATE_LIMITED_RETRIES {
+                    retries += 1;
+                    continue;
+                }
+            }
+
+            return outcome;
+        }
+    }
+
+    /// Send the request to the candidate endpoints in order until one answers without a server error.
+    /// A request fails over to the next endpoint on a connection error, or on a timeout for a GET
+    /// or DELETE request only: a timed out render or upload may have been processed already.
+    fn send_to_endpoints<F>(
+        &self,
+        candidates: Vec<usize>,
+        auth_header: Option<(HeaderName, HeaderValue)>,
+        options: &CallOptions,
+        build: F,
+    ) -> Result<(usize, Response)>
+    where
+        F: Fn(&str) -> Result<RequestBuilder>,
+    {
+        let mut outcome = Err(CarboneError::Error(
+            "no endpoint to send the request to".to_string(),
+        ));
+
+        for index in candidates {
+            // a blocking request can not be interrupted once sent
+            options.check_cancelled()?;
+
+            let mut request = build(self.endpoints.url(index))?;
+
+            if let Some(timeout) = options.timeout {
+                request = request.timeout(timeout);
+            }
+
+            if !options.headers.is_empty() {
+                request = request.headers(options.headers.clone());
+            }
+
+            if let Some((name, value)) = &auth_header {
+                request = request.header(name.clone(), value.clone());
+            }
+
+            let request = self.prepare(request)?;
+            let idempotent = matches!(*request.method(), Method::GET | Method::DELETE);
+
+            match self.execute(request) {
+                Ok(response) if response.status().is_server_error() => {
+                    self.endpoints.record_failure(index);
+                    outcome = Ok((index, response));
+                }
+                Ok(response) => {
+                    self.endpoints.record_success(index);
+                    return Ok((index, response));
+                }
+                Err(e) if e.is_connect() || (e.is_timeout() && idempotent) => {
+                    self.endpoints.record_failure(index);
+                    outcome = Err(e.into());
+                }
+                Err(e) => {
+                    if e.is_timeout() {
+                        self.endpoints.record_failure(index);
+                    }
+                    return Err(e.into());
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// Build the request and run the middlewares on it.
+    fn prepare(&self, request: RequestBuilder) -> Result<Request> {
+        let mut request = request.build()?;
+
+        if self.middlewares.is_empty() {
+            return Ok(request);
+        }
+
+        // the middlewares see an async request, mirroring the blocking one
+        let mut mirror = reqwest::Request::new(request.method().clone(), request.url().clone());
+        *mirror.headers_mut() = request.headers().clone();
+        *mirror.timeout_mut() = request.timeout().copied();
+
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            *mirror.body_mut() = Some(body.to_vec().into());
+        }
+
+        for middleware in &self.middlewares {
+            middleware.on_request(&mut mirror)?;
+        }
+
+        *request.method_mut() = mirror.method().clone();
+        *request.url_mut() = mirror.url().clone();
+        *request.headers_mut() = mirror.headers().clone();
+        *request.timeout_mut() = mirror.timeout().copied();
+
+        if let Some(body) = mirror.body().and_then(|body| body.as_bytes()) {
+            *request.body_mut() = Some(body.to_vec().into());
+        }
+
+        Ok(request)
+    }
+
+    /// Send the request and show the response to the middlewares.
+    fn execute(&self, request: Request) -> reqwest::Result<Response> {
+        let method = request.method().clone();
+        let url = request.url().clone();
+        let started_at = Instant::now();
+
+        let response = self.http_client.execute(request)?;
+
+        if !self.middlewares.is_empty() {
+            let response_meta = ResponseMeta {
+                method,
+                url,
+                status: response.status(),
+                headers: response.headers().clone(),
+                elapsed: started_at.elapsed(),
+            };
+
+            for middleware in &self.middlewares {
+                middleware.on_response(&response_meta);
+            }
+        }
+
+        Ok(response)
     }
 
     // Delete a template from the Carbone Service.
@@ -86,21 +362,25 @@ impl<'a> Carbone<'a> {
     /// }
     /// ```
     pub fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
-        let url = format!("{}/template/{}", self.config.api_url, template_id.as_str());
+        self.delete_template_with(template_id, &CallOptions::default())
+    }
 
-        let response = self.http_client.delete(url).send();
+    pub fn delete_template_with(
+        &self,
+        template_id: TemplateId,
+        options: &CallOptions,
+    ) -> Result<bool> {
+        let response = self.send(Call::new(Operation::DeleteTemplate), options, |api_url| {
+            let url = format!("{}/template/{}", api_url, template_id.as_str());
+            Ok(self.http_client.delete(url))
+        })?;
 
-        match response {
-            Ok(response) => {
-                let json = response.json::<APIResponse>()?;
+        let json = response.json::<APIResponse>()?;
 
-                if json.success {
-                    Ok(true)
-                } else {
-                    Err(CarboneError::Error(json.error.unwrap()))
-                }
-            }
-            Err(e) => Err(CarboneError::RequestError(e)),
+        if json.success {
+            Ok(true)
+        } else {
+            Err(CarboneError::Error(json.error.unwrap()))
         }
     }
 
@@ -142,20 +422,28 @@ impl<'a> Carbone<'a> {
     /// }
     /// ```
     pub fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
-        let url = format!("{}/template/{}", self.config.api_url, template_id.as_str());
+        self.download_template_with(template_id, &CallOptions::default())
+    }
+
+    pub fn download_template_with(
+        &self,
+        template_id: &TemplateId,
+        options: &CallOptions,
+    ) -> Result<Bytes> {
+        let call = Call::new(Operation::DownloadTemplate);
 
-        let response = self.http_client.get(url).send();
+        let response = self.send(call.clone(), options, |api_url| {
+            let url = format!("{}/template/{}", api_url, template_id.as_str());
+            Ok(self.http_client.get(url))
+        })?;
 
-        match response {
-            Ok(r) => {
-                if r.status() == StatusCode::OK {
-                    Ok(r.bytes()?)
-                } else {
-                    let json = r.json::<APIResponse>()?;
-                    Err(CarboneError::Error(json.error.unwrap()))
-                }
-            }
-            Err(e) => Err(CarboneError::RequestError(e)),
+        if response.status() == StatusCode::OK {
+            let content = response.bytes()?;
+            call.record_bytes_received(content.len());
+            Ok(content)
+        } else {
+            let json = response.json::<APIResponse>()?;
+            Err(CarboneError::Error(json.error.unwrap()))
         }
     }
 
@@ -212,21 +500,32 @@ impl<'a> Carbone<'a> {
         json_data: JsonData,
         payload: Option<&str>,
     ) -> Result<Bytes> {
-        
+        self.generate_report_with_file_with(
+            template_file,
+            json_data,
+            payload,
+            &CallOptions::default(),
+        )
+    }
+
+    pub fn generate_report_with_file_with(
+        &self,
+        template_file: &TemplateFile,
+        json_data: JsonData,
+        payload: Option<&str>,
+        options: &CallOptions,
+    ) -> Result<Bytes> {
         let template_id_generated = template_file.generate_id(payload)?;
 
-        let result = self.download_template(&template_id_generated);
+        let result = self.download_template_with(&template_id_generated, options);
 
-        let template_id = if result.is_err() {
-            self.upload_template(&template_file, None)?
-        } else {
-            template_id_generated
+        let template_id = match result {
+            Err(CarboneError::Cancelled) => return Err(CarboneError::Cancelled),
+            Err(_) => self.upload_template_with(template_file, None, options)?,
+            Ok(_) => template_id_generated,
         };
 
-        let render_id = self.render_data(template_id, json_data)?;
-        let report_content = self.get_report(&render_id)?;
-
-        Ok(report_content)
+        self.generate_report_with_template_id_with(template_id, json_data, options)
     }
 
     /// Get a new report.
@@ -265,23 +564,74 @@ impl<'a> Carbone<'a> {
     /// }
     /// ```
     pub fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
-        let url = format!("{}/render/{}", self.config.api_url, render_id.as_str());
+        self.get_report_with(render_id, &CallOptions::default())
+    }
 
-        let response = self.http_client.get(url).send();
+    pub fn get_report_with(&self, render_id: &RenderId, options: &CallOptions) -> Result<Bytes> {
+        Ok(self.fetch_report_with(render_id, options)?.content)
+    }
 
-        match response {
-            Ok(r) => {
-                if r.status() == StatusCode::OK {
-                    Ok(r.bytes()?)
-                } else {
-                    let json = r.json::<APIResponse>()?;
-                    Err(CarboneError::Error(json.error.unwrap()))
-                }
-            }
-            Err(e) => Err(CarboneError::RequestError(e)),
+    /// Get a report with its file name and content type, see [`Report`].
+    pub fn fetch_report(&self, render_id: &RenderId) -> Result<Report> {
+        self.fetch_report_with(render_id, &CallOptions::default())
+    }
+
+    pub fn fetch_report_with(&self, render_id: &RenderId, options: &CallOptions) -> Result<Report> {
+        let call = Call::new(Operation::GetReport)
+            .format(render_id.as_str().rsplit_once('.').map(|(_, ext)| ext));
+
+        // the report is only available on the node which rendered it
+        let candidates = match self.endpoints.pinned(render_id) {
+            Some(index) => vec![index],
+            None => self.endpoints.candidates(),
+        };
+
+        let (_, response) = self.send_to(call.clone(), candidates, options, |api_url| {
+            let url = format!("{}/render/{}", api_url, render_id.as_str());
+            Ok(self.http_client.get(url))
+        })?;
+
+        if response.status() == StatusCode::OK {
+            self.endpoints.unpin(render_id);
+            let headers = response.headers().clone();
+            let content = response.bytes()?;
+            call.record_bytes_received(content.len());
+            Ok(Report::from_response(render_id.clone(), &headers, content))
+        } else {
+            let json = response.json::<APIResponse>()?;
+            Err(CarboneError::Error(json.error.unwrap()))
         }
     }
 
+    /// Get a report and write it to a file, replacing an existing one. When the path
+    /// is a directory, the report is saved into it under its name, see [`Report::save_to`].
+    /// Returns the path of the file.
+    pub fn get_report_to_file<P: AsRef<Path>>(
+        &self,
+        render_id: &RenderId,
+        path: P,
+    ) -> Result<PathBuf> {
+        self.get_report_to_file_with(
+            render_id,
+            path,
+            OnConflict::Overwrite,
+            &CallOptions::default(),
+        )
+    }
+
+    /// Get a report and write it to a file, doing what `on_conflict` says when the
+    /// file exists, with the options of the call, see [`CallOptions`].
+    pub fn get_report_to_file_with<P: AsRef<Path>>(
+        &self,
+        render_id: &RenderId,
+        path: P,
+        on_conflict: OnConflict,
+        options: &CallOptions,
+    ) -> Result<PathBuf> {
+        let report = self.fetch_report_with(render_id, options)?;
+        report.save(path.as_ref(), on_conflict)
+    }
+
     /// Generate a report with a template_id given.
     ///
     ///
@@ -333,12 +683,87 @@ impl<'a> Carbone<'a> {
         template_id: TemplateId,
         json_data: JsonData,
     ) -> Result<Bytes> {
-        let render_id = self.render_data(template_id, json_data)?;
-        let report_content = self.get_report(&render_id)?;
+        self.generate_report_with_template_id_with(template_id, json_data, &CallOptions::default())
+    }
+
+    pub fn generate_report_with_template_id_with(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+        options: &CallOptions,
+    ) -> Result<Bytes> {
+        let cache_key = self.cache_key(&template_id, &json_data, options);
+
+        if let Some(content) = self.cached_report(cache_key.as_ref()) {
+            return Ok(content);
+        }
+
+        let render_id = self.render_data_with(template_id, json_data, options)?;
+        let report_content = self.get_report_with(&render_id, options)?;
+
+        if let (Some(report_cache), Some(cache_key)) = (&self.report_cache, &cache_key) {
+            report_cache.put(cache_key, &report_content);
+        }
 
         Ok(report_content)
     }
 
+    /// The key of a render in the report cache, built from the body sent to Carbone.
+    /// The report is not cached when the key cannot be computed, e.g. from a body
+    /// which is not JSON, the render reporting the problem if there is one.
+    fn cache_key(
+        &self,
+        template_id: &TemplateId,
+        json_data: &JsonData,
+        options: &CallOptions,
+    ) -> Option<CacheKey> {
+        self.report_cache.as_ref()?;
+
+        let json_data = self.render_body(json_data, options).ok()?;
+
+        CacheKey::new(template_id.clone(), &json_data).ok()
+    }
+
+    /// The render body sent to Carbone: the render settings and the translations applied.
+    fn render_body(&self, json_data: &JsonData, options: &CallOptions) -> Result<JsonData> {
+        let json_data = options
+            .render_settings
+            .or(&self.config.render_defaults)
+            .apply(json_data)?;
+
+        match options.translations.as_ref().or(self.translations.as_ref()) {
+            Some(translations) => translations.apply(&json_data, None),
+            None => Ok(json_data),
+        }
+    }
+
+    fn cached_report(&self, cache_key: Option<&CacheKey>) -> Option<Bytes> {
+        self.report_cache.as_ref()?.get(cache_key?)
+    }
+
+    /// Render a report and get it, along with the template_id and the hash of the render body,
+    /// e.g. to add it to a [`ReportArchive`](crate::report::ReportArchive).
+    pub fn render_report(&self, template_id: TemplateId, json_data: JsonData) -> Result<Report> {
+        self.render_report_with(template_id, json_data, &CallOptions::default())
+    }
+
+    pub fn render_report_with(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+        options: &CallOptions,
+    ) -> Result<Report> {
+        let data_hash = data_hash(&json_data);
+
+        let render_id = self.render_data_with(template_id.clone(), json_data, options)?;
+        let mut report = self.fetch_report_with(&render_id, options)?;
+
+        report.template_id = Some(template_id);
+        report.data_hash = Some(data_hash);
+
+        Ok(report)
+    }
+
     /// Render data with a given template_id.
     ///
     ///
@@ -385,26 +810,40 @@ impl<'a> Carbone<'a> {
     /// }
     /// ```
     pub fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
-        let url = format!("{}/render/{}", self.config.api_url, template_id.as_str());
-
-        let response = self
-            .http_client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .body(json_data.as_str().to_owned())
-            .send();
-
-        match response {
-            Ok(response) => {
-                let json = response.json::<APIResponse>()?;
-
-                if json.success {
-                    Ok(json.data.unwrap().render_id.unwrap())
-                } else {
-                    Err(CarboneError::Error(json.error.unwrap()))
-                }
-            }
-            Err(e) => Err(CarboneError::RequestError(e)),
+        self.render_data_with(template_id, json_data, &CallOptions::default())
+    }
+
+    pub fn render_data_with(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+        options: &CallOptions,
+    ) -> Result<RenderId> {
+        let json_data = self.render_body(&json_data, options)?;
+
+        let call = Call::new(Operation::Render)
+            .render_format(json_data.as_str())
+            .bytes_sent(json_data.as_str().len());
+
+        let (index, response) =
+            self.send_to(call, self.endpoints.candidates(), options, |api_url| {
+                let url = format!("{}/render/{}", api_url, template_id.as_str());
+
+                Ok(self
+                    .http_client
+                    .post(url)
+                    .header("Content-Type", "application/json")
+                    .body(json_data.as_str().to_owned()))
+            })?;
+
+        let json = response.json::<APIResponse>()?;
+
+        if json.success {
+            let render_id = json.data.unwrap().render_id.unwrap();
+            self.endpoints.pin(&render_id, index);
+            Ok(render_id)
+        } else {
+            Err(CarboneError::Error(json.error.unwrap()))
         }
     }
 
@@ -447,31 +886,239 @@ impl<'a> Carbone<'a> {
         &self,
         template_file: &TemplateFile,
         salt: Option<&str>,
+    ) -> Result<TemplateId> {
+        self.upload_template_with(template_file, salt, &CallOptions::default())
+    }
+
+    pub fn upload_template_with(
+        &self,
+        template_file: &TemplateFile,
+        salt: Option<&str>,
+        options: &CallOptions,
     ) -> Result<TemplateId> {
         let salt = match salt {
             Some(s) => s.to_string(),
             None => "".to_string(),
         };
 
-        let form = multipart::Form::new()
-            .text("", salt)
-            .file("template", template_file.path_as_str())?;
+        let file_content = template_file.read_content()?;
+        let mime = TemplateFormat::sniff(&file_content)?.mime_type();
 
-        let url = format!("{}/template", self.config.api_url);
+        let file_name = template_file.name().to_string();
 
-        let response = self.http_client.post(url).multipart(form).send();
+        let call = Call::new(Operation::UploadTemplate).bytes_sent(file_content.len());
 
-        match response {
-            Ok(response) => {
-                let json = response.json::<APIResponse>()?;
+        let response = self.send(call, options, |api_url| {
+            let part = multipart::Part::bytes(file_content.clone())
+                .file_name(file_name.clone())
+                .mime_str(mime)?;
 
-                if json.success {
-                    Ok(json.data.unwrap().template_id.unwrap())
-                } else {
-                    Err(CarboneError::Error(json.error.unwrap()))
-                }
-            }
-            Err(e) => Err(CarboneError::RequestError(e)),
+            let form = multipart::Form::new()
+                .text("", salt.clone())
+                .part("template", part);
+
+            let url = format!("{}/template", api_url);
+
+            Ok(self.http_client.post(url).multipart(form))
+        })?;
+
+        let json = response.json::<APIResponse>()?;
+
+        if json.success {
+            Ok(json.data.unwrap().template_id.unwrap())
+        } else {
+            Err(CarboneError::Error(json.error.unwrap()))
+        }
+    }
+
+    pub fn status(&self) -> Result<ServiceStatus> {
+        self.status_with(&CallOptions::default())
+    }
+
+    pub fn status_with(&self, options: &CallOptions) -> Result<ServiceStatus> {
+        let response = self.send(Call::new(Operation::Status), options, |api_url| {
+            let url = format!("{}/status", api_url);
+            Ok(self.http_client.get(url))
+        })?;
+
+        let status = response.status();
+        let body = response.bytes()?;
+
+        serde_json::from_slice::<ServiceStatus>(&body).map_err(|_| {
+            CarboneError::ResponseError(format!(
+                "unexpected status response {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            ))
+        })
+    }
+
+    pub fn negotiate_version(&self, check: VersionCheck) -> Result<ServiceStatus> {
+        let mut status = self.status()?;
+
+        if let Some(version) = status.api_version() {
+            self.server_api_version.store(version, Ordering::Relaxed);
+        }
+
+        check.check(&mut status, &self.config.api_version)?;
+
+        Ok(status)
+    }
+}
+
+/// Build a blocking Carbone client.
+///
+/// By default the requests are sent to the `api_url` of the config,
+/// authenticated with its `api_token` when one is set.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rs::auth::Auth;
+/// use carbone_sdk_rs::blocking::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::endpoint::{Endpoint, EndpointPool};
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_env()?;
+///
+///     let endpoints = EndpointPool::new(vec![
+///         Endpoint::new("http://carbone-1:4000", 1)?,
+///         Endpoint::new("http://carbone-2:4000", 1)?,
+///     ])?;
+///
+///     let carbone = Carbone::builder(&config)
+///         .auth(Auth::None)
+///         .endpoints(Arc::new(endpoints))
+///         .build()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CarboneBuilder<'a> {
+    config: &'a Config,
+    auth: Option<Auth>,
+    endpoints: Option<Arc<EndpointPool>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    report_cache: Option<Arc<dyn ReportCache>>,
+    translations: Option<Arc<Translations>>,
+}
+
+impl<'a> CarboneBuilder<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            auth: None,
+            endpoints: None,
+            circuit_breaker: None,
+            rate_limiter: None,
+            middlewares: Vec::new(),
+            report_cache: None,
+            translations: None,
         }
     }
+
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Spread the requests over several endpoints, the pool can be shared
+    /// by several clients and kept to observe the health of the endpoints.
+    pub fn endpoints(mut self, endpoints: Arc<EndpointPool>) -> Self {
+        self.endpoints = Some(endpoints);
+        self
+    }
+
+    /// Share a circuit breaker between clients instead of
+    /// creating one from the `circuit_breaker` of the config.
+    pub fn circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Wait for the permits of a rate limiter, which can be shared between clients.
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Add a middleware run for every request, after the ones already added.
+    pub fn middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.middlewares.push(middleware);
+        self
+    }
+
+    /// Look the reports up in a cache before rendering them with
+    /// `generate_report_with_template_id`, see [`ReportCache`].
+    pub fn report_cache(mut self, report_cache: Arc<dyn ReportCache>) -> Self {
+        self.report_cache = Some(report_cache);
+        self
+    }
+
+    /// Attach the translations of the `lang` of each render body, see [`Translations`].
+    pub fn translations(mut self, translations: Arc<Translations>) -> Self {
+        self.translations = Some(translations);
+        self
+    }
+
+    pub fn build(self) -> Result<Carbone<'a>> {
+        let config = self.config;
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert(
+            "carbone-version",
+            HeaderValue::from_str(config.api_version.as_str()).unwrap(),
+        );
+
+        let http_client = ClientBuilder::new()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(config.api_timeout))
+            .build()?;
+
+        let auth = match (self.auth, &config.api_token) {
+            (Some(auth), _) => auth,
+            (None, Some(api_token)) => Auth::bearer(api_token.clone()),
+            (None, None) => Auth::None,
+        };
+
+        let endpoints = match self.endpoints {
+            Some(endpoints) => endpoints,
+            None => Arc::new(EndpointPool::new(vec![Endpoint::new(
+                config.api_url.as_str(),
+                1,
+            )?])?),
+        };
+
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+
+        let circuit_breaker = match (self.circuit_breaker, config.circuit_breaker) {
+            (Some(circuit_breaker), _) => Some(circuit_breaker),
+            (None, Some(circuit_breaker_config)) => {
+                Some(Arc::new(CircuitBreaker::new(circuit_breaker_config)))
+            }
+            (None, None) => None,
+        };
+
+        Ok(Carbone {
+            config,
+            http_client,
+            auth,
+            endpoints,
+            circuit_breaker,
+            rate_limiter: self.rate_limiter,
+            middlewares: self.middlewares,
+            report_cache: self.report_cache,
+            translations: self.translations,
+            server_api_version: Arc::new(AtomicU32::new(0)),
+            runtime: Arc::new(runtime),
+        })
+    }
 }
@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::Bytes;
+use serde_json::Value;
+
+use crate::errors::CarboneError;
+use crate::report::{sha256, write_atomic, OnConflict};
+use crate::template::TemplateId;
+use crate::types::JsonData;
+
+use crate::types::Result;
+
+// extension of the reports stored by a `DiskReportCache`
+const DISK_EXTENSION: &str = "report";
+
+/// Identifies a rendered report: the template and the sha256 of the normalized render body.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub template_id: TemplateId,
+    pub data_hash: String,
+}
+
+impl CacheKey {
+    /// Create the key of a render, the order of the keys and the
+    /// whitespaces of the body do not change the key.
+    pub fn new(template_id: TemplateId, json_data: &JsonData) -> Result<Self> {
+        let body: Value = serde_json::from_str(json_data.as_str())
+            .or(Err(CarboneError::RequestBodyNotWellFormedJsonError))?;
+
+        let mut normalized = String::new();
+        normalize(&body, &mut normalized);
+
+        Ok(Self {
+            template_id,
+            data_hash: sha256(normalized.as_bytes()),
+        })
+    }
+
+    /// A digest of the whole key, usable as a file name.
+    pub fn digest(&self) -> String {
+        sha256(format!("{}\n{}", self.template_id.as_str(), self.data_hash).as_bytes())
+    }
+}
+
+/// Write a JSON value with its object keys sorted.
+fn normalize(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                normalize(value, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                normalize(value, out);
+            }
+            out.push('}');
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+/// A cache of the rendered reports, consulted by `generate_report_with_template_id`
+/// before calling the Carbone API, by the async and the blocking clients.
+///
+/// A cache is best effort: a report which cannot be stored is simply rendered again.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::env;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use carbone_sdk_rs::cache::MemoryReportCache;
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::template::TemplateId;
+/// use carbone_sdk_rs::types::JsonData;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_env()?;
+///
+///     // 64 MB of reports, kept for an hour
+///     let cache = MemoryReportCache::new(64 * 1024 * 1024).ttl(Duration::from_secs(3600));
+///
+///     let carbone = Carbone::builder(&config)
+///         .report_cache(Arc::new(cache))
+///         .build()?;
+///
+///     let template_id = TemplateId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string())?;
+///     let json_data = JsonData::new(r#"{ "data": {}, "convertTo": "pdf" }"#.to_string())?;
+///
+///     // the second report comes from the cache
+///     let report_content = carbone.generate_report_with_template_id(template_id.clone(), json_data.clone()).await?;
+///     let report_content = carbone.generate_report_with_template_id(template_id, json_data).await?;
+///
+///     Ok(())
+/// }
+/// ```
+pub trait ReportCache: Send + Sync + fmt::Debug {
+    fn get(&self, key: &CacheKey) -> Option<Bytes>;
+
+    fn put(&self, key: &CacheKey, content: &Bytes);
+}
+
+#[derive(Debug)]
+struct MemoryEntry {
+    content: Bytes,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    entries: HashMap<CacheKey, MemoryEntry>,
+    // the keys by last use, the least recently used first
+    recent: BTreeMap<u64, CacheKey>,
+    size: u64,
+    tick: u64,
+}
+
+impl MemoryState {
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recent.remove(&entry.last_used);
+            self.size -= entry.content.len() as u64;
+        }
+    }
+}
+
+/// An in-memory `ReportCache` of at most `max_size` bytes of reports,
+/// evicting the least recently used ones.
+#[derive(Debug)]
+pub struct MemoryReportCache {
+    max_size: u64,
+    ttl: Option<Duration>,
+    state: Mutex<MemoryState>,
+}
+
+impl MemoryReportCache {
+    pub fn new(max_size: u64) -> Self {
+        Self {
+            max_size,
+            ttl: None,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    /// Forget the reports stored for longer than `ttl`.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The number of reports in the cache.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The size of the reports in the cache, in bytes.
+    pub fn size(&self) -> u64 {
+        self.state.lock().unwrap().size
+    }
+}
+
+impl ReportCache for MemoryReportCache {
+    fn get(&self, key: &CacheKey) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap();
+
+        let expired = match (state.entries.get(key), self.ttl) {
+            (None, _) => return None,
+            (Some(entry), Some(ttl)) => entry.stored_at.elapsed() >= ttl,
+            (Some(_), None) => false,
+        };
+
+        if expired {
+            state.remove(key);
+            return None;
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+
+        let entry = state.entries.get_mut(key)?;
+        let last_used = std::mem::replace(&mut entry.last_used, tick);
+        let content = entry.content.clone();
+
+        state.recent.remove(&last_used);
+        state.recent.insert(tick, key.clone());
+
+        Some(content)
+    }
+
+    fn put(&self, key: &CacheKey, content: &Bytes) {
+        let size = content.len() as u64;
+        if size > self.max_size {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.remove(key);
+
+        while state.size + size > self.max_size {
+            let oldest = match state.recent.first_key_value() {
+                Some((_, oldest)) => oldest.clone(),
+                None => break,
+            };
+            state.remove(&oldest);
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+
+        state.entries.insert(
+            key.clone(),
+            MemoryEntry {
+                content: content.clone(),
+                stored_at: Instant::now(),
+                last_used: tick,
+            },
+        );
+        state.recent.insert(tick, key.clone());
+        state.size += size;
+    }
+}
+
+/// A `ReportCache` storing the reports as files of a directory, which can be shared
+/// by several processes, of at most `max_size` bytes of reports, evicting the oldest ones.
+#[derive(Debug)]
+pub struct DiskReportCache {
+    dir: PathBuf,
+    max_size: u64,
+    ttl: Option<Duration>,
+    // serializes the evictions of the process
+    lock: Mutex<()>,
+}
+
+impl DiskReportCache {
+    /// Create a cache in a directory, created if needed.
+    pub fn new<P: AsRef<Path>>(dir: P, max_size: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        if !dir.is_dir() {
+            return Err(CarboneError::IsADirectory(dir.display().to_string()));
+        }
+
+        Ok(Self {
+            dir,
+            max_size,
+            ttl: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Remove the reports stored for longer than `ttl`.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", key.digest(), DISK_EXTENSION))
+    }
+
+    fn is_expired(&self, modified: SystemTime) -> bool {
+        match self.ttl {
+            // a time in the future is not expired
+            Some(ttl) => modified.elapsed().is_ok_and(|elapsed| elapsed >= ttl),
+            None => false,
+        }
+    }
+
+    /// The reports of the directory, the oldest first.
+    fn entries(&self) -> Result<Vec<(SystemTime, u64, PathBuf)>> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some(DISK_EXTENSION) {
+                continue;
+            }
+
+            // removed by another process in the meantime
+            if let Ok(metadata) = fs::metadata(&path) {
+                entries.push((metadata.modified()?, metadata.len(), path));
+            }
+        }
+
+        entries.sort();
+
+        Ok(entries)
+    }
+
+    fn evict(&self, size: u64) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+
+        let mut total: u64 = 0;
+        let mut kept = Vec::new();
+
+        for (modified, len, path) in self.entries()? {
+            if self.is_expired(modified) {
+                let _ = fs::remove_file(&path);
+            } else {
+                total += len;
+                kept.push((len, path));
+            }
+        }
+
+        for (len, path) in kept {
+            if total + size <= self.max_size {
+                break;
+            }
+            let _ = fs::remove_file(&path);
+            total -= len;
+        }
+
+        Ok(())
+    }
+}
+
+impl ReportCache for DiskReportCache {
+    fn get(&self, key: &CacheKey) -> Option<Bytes> {
+        let path = self.path(key);
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+
+        if self.is_expired(modified) {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+
+        fs::read(&path).ok().map(Bytes::from)
+    }
+
+    fn put(&self, key: &CacheKey, content: &Bytes) {
+        let size = content.len() as u64;
+        if size > self.max_size {
+            return;
+        }
+
+        // the report is rendered again when it cannot be stored
+        if self.evict(size).is_ok() {
+            let _ = write_atomic(&self.path(key), content, OnConflict::Overwrite);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::errors::{CarboneError, ConfigError};
+
+use crate::types::Result;
+
+/// The circuit breaker settings of a Config.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::str::FromStr;
+///
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_str(r#"{
+///         "apiUrl": "http://127.0.0.1",
+///         "apiTimeout": 60,
+///         "apiVersion" : "4",
+///         "circuitBreaker": {
+///             "failureThreshold": 5,
+///             "openDuration": 30
+///         }
+///     }"#)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures after which the circuit opens.
+    #[serde(alias = "failure_threshold")]
+    pub failure_threshold: u32,
+    /// Seconds during which the requests fail fast before the circuit is half-open.
+    #[serde(alias = "open_duration")]
+    pub open_duration: u64,
+    /// Requests let through to probe the Carbone API while the circuit is half-open.
+    #[serde(alias = "half_open_probes")]
+    pub half_open_probes: u32,
+}
+
+impl CircuitBreakerConfig {
+    pub fn validate(&self) -> std::result::Result<(), ConfigError> {
+        let zero = [
+            ("failureThreshold", self.failure_threshold as u64),
+            ("openDuration", self.open_duration),
+            ("halfOpenProbes", self.half_open_probes as u64),
+        ];
+
+        match zero.iter().find(|(_, value)| *value == 0) {
+            Some((field, _)) => Err(ConfigError::InvalidValue(
+                format!("circuitBreaker.{}", field),
+                "0".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: 30,
+            half_open_probes: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// The requests are sent.
+    Closed,
+    /// The requests fail fast with `CarboneError::CircuitOpen`.
+    Open,
+    /// A limited number of requests is sent to probe the Carbone API.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probes: u32,
+}
+
+/// Stop sending requests to a Carbone API which keeps failing.
+///
+/// Connection errors, timeouts and server errors are failures.
+/// It is shared by the clones of a client, and can be kept to observe its state.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probes: 0,
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// The current state, an open circuit whose open duration elapsed is half-open.
+    pub fn state(&self) -> CircuitState {
+        let inner = self.inner.lock().unwrap();
+
+        if inner.state == CircuitState::Open && self.open_duration_elapsed(&inner) {
+            CircuitState::HalfOpen
+        } else {
+            inner.state
+        }
+    }
+
+    fn open_duration_elapsed(&self, inner: &Inner) -> bool {
+        let open_duration = Duration::from_secs(self.config.open_duration);
+
+        inner
+            .opened_at
+            .map(|opened_at| opened_at.elapsed() >= open_duration)
+            .unwrap_or(true)
+    }
+
+    /// Check that a request can be sent, the permit gives back its probe
+    /// when dropped before the outcome of the request is recorded.
+    pub(crate) fn acquire(&self) -> Result<CircuitPermit<'_>> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.state == CircuitState::Open && self.open_duration_elapsed(&inner) {
+            inner.state = CircuitState::HalfOpen;
+            inner.probes = 0;
+        }
+
+        match inner.state {
+            CircuitState::Closed => {}
+            CircuitState::HalfOpen if inner.probes < self.config.half_open_probes => {
+                inner.probes += 1;
+            }
+            _ => return Err(CarboneError::CircuitOpen),
+        }
+
+        Ok(CircuitPermit {
+            circuit_breaker: self,
+            recorded: false,
+        })
+    }
+
+    fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.probes = 0;
+    }
+
+    /// Give back the probe of a request which failed before reaching the Carbone API.
+    fn release(&self) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.state == CircuitState::HalfOpen {
+            inner.probes = inner.probes.saturating_sub(1);
+        }
+    }
+
+    fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.consecutive_failures += 1;
+
+        let open = match inner.state {
+            CircuitState::Closed => inner.consecutive_failures >= self.config.failure_threshold,
+            CircuitState::HalfOpen => true,
+            CircuitState::Open => false,
+        };
+
+        if open {
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(Instant::now());
+            inner.probes = 0;
+        }
+    }
+}
+
+/// The permission to send a request given by `CircuitBreaker::acquire`.
+///
+/// The probe of a half-open circuit is given back when the permit is dropped without
+/// an outcome, e.g. when the request failed before reaching the Carbone API or when
+/// the future sending it was cancelled.
+#[derive(Debug)]
+pub(crate) struct CircuitPermit<'a> {
+    circuit_breaker: &'a CircuitBreaker,
+    recorded: bool,
+}
+
+impl CircuitPermit<'_> {
+    pub(crate) fn record_success(mut self) {
+        self.recorded = true;
+        self.circuit_breaker.record_success();
+    }
+
+    pub(crate) fn record_failure(mut self) {
+        self.recorded = true;
+        self.circuit_breaker.record_failure();
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.circuit_breaker.release();
+        }
+    }
+}
@@ -1,24 +1,42 @@
 pub const CARBONE_API_URL: &str = "https://api.carbone.io";
 pub const CARBONE_API_VERSION: &str = "4";
 
-use anyhow::{anyhow, Result};
+pub const ENV_API_URL: &str = "CARBONE_API_URL";
+pub const ENV_API_VERSION: &str = "CARBONE_API_VERSION";
+pub const ENV_API_TIMEOUT: &str = "CARBONE_API_TIMEOUT";
+pub const ENV_TOKEN: &str = "CARBONE_TOKEN";
+pub const ENV_LANG: &str = "CARBONE_LANG";
+pub const ENV_TIMEZONE: &str = "CARBONE_TIMEZONE";
 
-use validator::Validate;
+use reqwest::Url;
 
-use crate::errors::CarboneError;
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::errors::{CarboneError, ConfigError};
+use crate::locale::{Lang, RenderSettings, Timezone};
 use serde::Deserialize;
+use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use crate::types::ApiVersion;
+use crate::types::{ApiJsonToken, ApiVersion};
 
-#[derive(Debug, Clone, Deserialize, Validate, PartialEq, Eq)]
+use crate::types::Result;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
-    #[validate(url)]
     pub api_url: String,
     pub api_timeout: u64,
     pub api_version: ApiVersion,
+    #[serde(default)]
+    pub api_token: Option<ApiJsonToken>,
+    /// Fail fast while the Carbone API is down, disabled when not set.
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// The lang, timezone and currencies of every render, unless the call sets them.
+    #[serde(default)]
+    pub render_defaults: RenderSettings,
 }
 
 impl Config {
@@ -48,6 +66,9 @@ impl Config {
             api_url,
             api_timeout,
             api_version,
+            api_token: None,
+            circuit_breaker: None,
+            render_defaults: Default::default(),
         };
 
         config.validate()?;
@@ -75,6 +96,208 @@ impl Config {
         let file_content =
             fs::read_to_string(path).or(Err(CarboneError::FileNotFound(path.to_string())))?;
         let config: Self = Self::from_str(file_content.as_str())?;
+        Ok(config)
+    }
+
+    /// Load a Configuration from the environment.
+    ///
+    /// The default values are overridden by the variables `CARBONE_API_URL`, `CARBONE_API_VERSION`,
+    /// `CARBONE_API_TIMEOUT`, `CARBONE_TOKEN`, `CARBONE_LANG` and `CARBONE_TIMEZONE`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    ///
+    /// use carbone_sdk_rs::config::Config;
+    /// use carbone_sdk_rs::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///     let config = Config::from_env()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_env() -> Result<Self> {
+        ConfigLoader::new().env().load()
+    }
+
+    /// Check the api_url, the api_timeout, the api_version and the circuit breaker of the Config.
+    pub fn validate(&self) -> std::result::Result<(), ConfigError> {
+        validate_url(&self.api_url)?;
+
+        if self.api_timeout == 0 {
+            return Err(ConfigError::ZeroTimeout);
+        }
+
+        if let Some(circuit_breaker) = &self.circuit_breaker {
+            circuit_breaker.validate()?;
+        }
+
+        self.api_version.validate()
+    }
+}
+
+/// The optional values of a Config, used to layer several configuration sources.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialConfig {
+    #[serde(alias = "api_url")]
+    pub api_url: Option<String>,
+    #[serde(alias = "api_timeout")]
+    pub api_timeout: Option<u64>,
+    #[serde(alias = "api_version")]
+    pub api_version: Option<ApiVersion>,
+    #[serde(alias = "api_token")]
+    pub api_token: Option<ApiJsonToken>,
+    #[serde(alias = "circuit_breaker")]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Merged with the render defaults of the lower sources, setting by setting.
+    #[serde(alias = "render_defaults")]
+    pub render_defaults: Option<RenderSettings>,
+}
+
+impl PartialConfig {
+    /// Load the values from a JSON, TOML or YAML file, depending on its extension.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let file_content = fs::read_to_string(path)
+            .or(Err(CarboneError::FileNotFound(path.display().to_string())))?;
+
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+
+        let partial_config = match extension {
+            "json" => serde_json::from_str(&file_content).map_err(json_error)?,
+            #[cfg(feature = "toml")]
+            "toml" => toml::from_str(&file_content).map_err(|e| toml_error(&file_content, e))?,
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => serde_yaml::from_str(&file_content).map_err(yaml_error)?,
+            _ => {
+                let path = path.display().to_string();
+                return Err(ConfigError::UnsupportedFormat(path).into());
+            }
+        };
+
+        Ok(partial_config)
+    }
+
+    /// Load the values from the `CARBONE_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let api_timeout = match env::var(ENV_API_TIMEOUT) {
+            Ok(v) => match v.parse::<u64>() {
+                Ok(timeout) => Some(timeout),
+                Err(_) => {
+                    return Err(ConfigError::InvalidValue(ENV_API_TIMEOUT.to_string(), v).into())
+                }
+            },
+            Err(_) => None,
+        };
+
+        let render_defaults = RenderSettings {
+            lang: env::var(ENV_LANG).ok().map(Lang::new).transpose()?,
+            timezone: env::var(ENV_TIMEZONE).ok().map(Timezone::new).transpose()?,
+            ..Default::default()
+        };
+
+        Ok(Self {
+            api_url: env::var(ENV_API_URL).ok(),
+            api_timeout,
+            api_version: env::var(ENV_API_VERSION)
+                .ok()
+                .map(ApiVersion::new)
+                .transpose()?,
+            api_token: env::var(ENV_TOKEN)
+                .ok()
+                .map(ApiJsonToken::new)
+                .transpose()?,
+            circuit_breaker: None,
+            render_defaults: Some(render_defaults).filter(|settings| !settings.is_empty()),
+        })
+    }
+
+    fn apply(self, config: &mut Config) {
+        if let Some(api_url) = self.api_url {
+            config.api_url = api_url;
+        }
+        if let Some(api_timeout) = self.api_timeout {
+            config.api_timeout = api_timeout;
+        }
+        if let Some(api_version) = self.api_version {
+            config.api_version = api_version;
+        }
+        if let Some(api_token) = self.api_token {
+            config.api_token = Some(api_token);
+        }
+        if let Some(circuit_breaker) = self.circuit_breaker {
+            config.circuit_breaker = Some(circuit_breaker);
+        }
+        if let Some(render_defaults) = self.render_defaults {
+            config.render_defaults = render_defaults.or(&config.render_defaults);
+        }
+    }
+}
+
+/// Build a Config from several sources.
+///
+/// Whatever the order of the calls, the sources are layered as
+/// defaults < file < environment < overrides.
+///
+/// # Example
+///
+/// ```no_run
+///
+/// use carbone_sdk_rs::config::{ConfigLoader, PartialConfig};
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///     let config = ConfigLoader::new()
+///         .file("carbone.toml")
+///         .env()
+///         .overrides(PartialConfig {
+///             api_timeout: Some(10),
+///             ..Default::default()
+///         })
+///         .load()?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    file: Option<PathBuf>,
+    env: bool,
+    overrides: PartialConfig,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    pub fn env(mut self) -> Self {
+        self.env = true;
+        self
+    }
+
+    pub fn overrides(mut self, overrides: PartialConfig) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    pub fn load(self) -> Result<Config> {
+        let mut config: Config = Default::default();
+
+        if let Some(path) = &self.file {
+            PartialConfig::from_file(path)?.apply(&mut config);
+        }
+
+        if self.env {
+            PartialConfig::from_env()?.apply(&mut config);
+        }
+
+        self.overrides.apply(&mut config);
+
         config.validate()?;
         Ok(config)
     }
@@ -107,6 +330,9 @@ impl Default for Config {
             api_url: CARBONE_API_URL.to_string(),
             api_timeout: 60,
             api_version: ApiVersion::new(CARBONE_API_VERSION.to_string()).unwrap(),
+            api_token: None,
+            circuit_breaker: None,
+            render_defaults: Default::default(),
         }
     }
 }
@@ -136,15 +362,61 @@ impl Default for Config {
 /// }
 /// ```
 impl FromStr for Config {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        match serde_json::from_str(s) {
-            Ok(config) => Ok(config),
-            Err(e) => Err(anyhow!(format!(
-                "CarboneSDK FromStr JsonParseError: {}",
-                e.to_string()
-            ))),
-        }
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(s).map_err(json_error)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Check that an url is an http(s) url with a host.
+pub(crate) fn validate_url(api_url: &str) -> std::result::Result<(), ConfigError> {
+    match Url::parse(api_url) {
+        Ok(url) if ["http", "https"].contains(&url.scheme()) && url.has_host() => Ok(()),
+        _ => Err(ConfigError::InvalidUrl(api_url.to_string())),
+    }
+}
+
+fn json_error(e: serde_json::Error) -> ConfigError {
+    parse_error(e.to_string(), e.line(), e.column())
+}
+
+#[cfg(feature = "toml")]
+fn toml_error(content: &str, e: toml::de::Error) -> ConfigError {
+    let offset = e.span().map(|span| span.start).unwrap_or(0);
+    let before = &content[..offset.min(content.len())];
+
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+
+    parse_error(e.message().to_string(), line, column)
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_error(e: serde_yaml::Error) -> ConfigError {
+    let (line, column) = e
+        .location()
+        .map(|l| (l.line(), l.column()))
+        .unwrap_or((0, 0));
+
+    parse_error(e.to_string(), line, column)
+}
+
+/// Report a missing field on its own, as serde reports it as a parse error.
+fn parse_error(message: String, line: usize, column: usize) -> ConfigError {
+    let missing_field = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split_once('`'))
+        .map(|(field, _)| field.to_string());
+
+    match missing_field {
+        Some(field) => ConfigError::MissingField(field),
+        None => ConfigError::Parse {
+            message,
+            line,
+            column,
+        },
     }
 }
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::marker::{Marker, MarkerLocation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// What changed: a value marker, a loop (e.g. `d.products[i]`) or a condition
+/// (a marker using `ifEQ`, `show`, `hideBegin`...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSubject {
+    Loop,
+    Condition,
+    Marker,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateChange {
+    pub kind: ChangeKind,
+    pub subject: ChangeSubject,
+    /// The path of the marker or of the loop, e.g. `d.products[i].price`.
+    pub path: String,
+    /// The formatters used with the path in the old template, one entry per distinct chain.
+    pub before: Vec<String>,
+    /// The formatters used with the path in the new template, one entry per distinct chain.
+    pub after: Vec<String>,
+    /// Where the change is, in the new template or, for a removal, in the old one.
+    pub locations: Vec<MarkerLocation>,
+}
+
+/// The structural differences between two templates, computed from their markers
+/// rather than from their bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TemplateDiff {
+    pub changes: Vec<TemplateChange>,
+}
+
+impl TemplateDiff {
+    pub fn from_markers(old: &[Marker], new: &[Marker]) -> Self {
+        let mut changes = Vec::new();
+
+        changes.extend(diff_loops(old, new));
+
+        for subject in [ChangeSubject::Condition, ChangeSubject::Marker] {
+            let old = group_by_path(old, subject);
+            let new = group_by_path(new, subject);
+            changes.extend(diff_markers(&old, &new, subject));
+        }
+
+        changes.sort_by(|a, b| (&a.path, a.subject, a.kind).cmp(&(&b.path, b.subject, b.kind)));
+
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for TemplateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TemplateChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Modified => '~',
+        };
+        let subject = match self.subject {
+            ChangeSubject::Loop => "loop",
+            ChangeSubject::Condition => "condition",
+            ChangeSubject::Marker => "marker",
+        };
+
+        write!(f, "{} {} {}", sign, subject, self.path)?;
+
+        if self.kind == ChangeKind::Modified {
+            write!(
+                f,
+                ": {} -> {}",
+                formatters_text(&self.before),
+                formatters_text(&self.after)
+            )?;
+        }
+
+        let locations: Vec<String> = self
+            .locations
+            .iter()
+            .map(|location| match location.entry.is_empty() {
+                true => format!("offset {}", location.offset),
+                false => format!("{}@{}", location.entry, location.offset),
+            })
+            .collect();
+
+        if !locations.is_empty() {
+            write!(f, " ({})", locations.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn formatters_text(formatters: &[String]) -> String {
+    let formatters: Vec<&str> = formatters
+        .iter()
+        .map(|chain| match chain.is_empty() {
+            true => "no formatter",
+            false => chain.as_str(),
+        })
+        .collect();
+
+    match formatters.is_empty() {
+        true => "none".to_string(),
+        false => formatters.join(" | "),
+    }
+}
+
+fn diff_loops(old: &[Marker], new: &[Marker]) -> Vec<TemplateChange> {
+    let old_loops = loops(old);
+    let new_loops = loops(new);
+
+    let added = new_loops
+        .iter()
+        .filter(|(path, _)| !old_loops.contains_key(*path))
+        .map(|(path, location)| (ChangeKind::Added, path, location));
+
+    let removed = old_loops
+        .iter()
+        .filter(|(path, _)| !new_loops.contains_key(*path))
+        .map(|(path, location)| (ChangeKind::Removed, path, location));
+
+    added
+        .chain(removed)
+        .map(|(kind, path, location)| TemplateChange {
+            kind,
+            subject: ChangeSubject::Loop,
+            path: path.clone(),
+            before: Vec::new(),
+            after: Vec::new(),
+            locations: vec![location.clone()],
+        })
+        .collect()
+}
+
+/// The loops of a template with the location of their first marker.
+fn loops(markers: &[Marker]) -> BTreeMap<String, MarkerLocation> {
+    let mut loops = BTreeMap::new();
+
+    for marker in markers.iter() {
+        for path in marker.loops() {
+            loops.entry(path).or_insert_with(|| marker.location.clone());
+        }
+    }
+
+    loops
+}
+
+fn group_by_path(markers: &[Marker], subject: ChangeSubject) -> BTreeMap<String, Vec<&Marker>> {
+    let mut groups: BTreeMap<String, Vec<&Marker>> = BTreeMap::new();
+
+    for marker in markers.iter() {
+        if marker.is_condition() == (subject == ChangeSubject::Condition) {
+            groups.entry(marker.path_str()).or_default().push(marker);
+        }
+    }
+
+    groups
+}
+
+fn diff_markers(
+    old: &BTreeMap<String, Vec<&Marker>>,
+    new: &BTreeMap<String, Vec<&Marker>>,
+    subject: ChangeSubject,
+) -> Vec<TemplateChange> {
+    let paths: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let mut changes = Vec::new();
+
+    for path in paths {
+        let old_markers = old.get(path).map(Vec::as_slice).unwrap_or_default();
+        let new_markers = new.get(path).map(Vec::as_slice).unwrap_or_default();
+
+        let before = formatter_chains(old_markers);
+        let after = formatter_chains(new_markers);
+
+        let (kind, locations) = if old_markers.is_empty() {
+            (
+                ChangeKind::Added,
+                locations_except(new_markers, &BTreeSet::new()),
+            )
+        } else if new_markers.is_empty() {
+            (
+                ChangeKind::Removed,
+                locations_except(old_markers, &BTreeSet::new()),
+            )
+        } else if before != after {
+            // point at the new formatter chains, or at the dropped ones if there are none
+            let mut locations = locations_except(new_markers, &before);
+            if locations.is_empty() {
+                locations = locations_except(old_markers, &after);
+            }
+            (ChangeKind::Modified, locations)
+        } else {
+            continue;
+        };
+
+        changes.push(TemplateChange {
+            kind,
+            subject,
+            path: path.clone(),
+            before: before.into_iter().collect(),
+            after: after.into_iter().collect(),
+            locations,
+        });
+    }
+
+    changes
+}
+
+fn formatter_chains(markers: &[&Marker]) -> BTreeSet<String> {
+    markers
+        .iter()
+        .map(|marker| marker.formatters_str())
+        .collect()
+}
+
+/// The locations of the markers whose formatters are not in `except`.
+fn locations_except(markers: &[&Marker], except: &BTreeSet<String>) -> Vec<MarkerLocation> {
+    markers
+        .iter()
+        .filter(|marker| !except.contains(&marker.formatters_str()))
+        .map(|marker| marker.location.clone())
+        .collect()
+}
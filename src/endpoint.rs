@@ -0,0 +1,279 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::validate_url;
+use crate::errors::ConfigError;
+use crate::render::RenderId;
+
+use crate::types::Result;
+
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+pub const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+// render ids remembered to fetch their report from the node which rendered them
+const MAX_PINNED_RENDERS: usize = 1024;
+
+/// A Carbone API node and its share of the requests.
+///
+/// An endpoint with a weight of 0 only receives requests when the other ones fail,
+/// e.g. the Carbone Cloud API as a fallback of self-hosted nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    url: String,
+    weight: u32,
+}
+
+impl Endpoint {
+    pub fn new<T: Into<String>>(url: T, weight: u32) -> Result<Self> {
+        let url = url.into();
+        validate_url(&url)?;
+        Ok(Self { url, weight })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    consecutive_failures: u32,
+    unhealthy_since: Option<Instant>,
+    // smooth weighted round-robin state
+    current_weight: i64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    health: Vec<Health>,
+    pinned: HashMap<String, usize>,
+    pinned_order: VecDeque<String>,
+}
+
+/// A set of Carbone API nodes shared by the requests of a client.
+///
+/// The requests are spread over the healthy endpoints according to their weight
+/// and fail over to the next endpoint on connection errors and server errors, and on
+/// timeouts for the GET and DELETE requests only, which can safely be sent again.
+/// An endpoint is marked unhealthy after `failure_threshold` consecutive failures.
+/// Once `probe_interval` elapsed, a single request is sent to it first to probe it,
+/// the other requests keep going to the healthy endpoints until the probe succeeds.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::endpoint::{Endpoint, EndpointPool};
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///
+///     let endpoints = EndpointPool::new(vec![
+///         Endpoint::new("http://carbone-1:4000", 2)?,
+///         Endpoint::new("http://carbone-2:4000", 1)?,
+///         Endpoint::new("https://api.carbone.io", 0)?,
+///     ])?
+///     .failure_threshold(2)
+///     .probe_interval(Duration::from_secs(10));
+///
+///     let endpoints = Arc::new(endpoints);
+///     let carbone = Carbone::builder(&config).endpoints(endpoints.clone()).build()?;
+///
+///     assert!(endpoints.is_healthy("http://carbone-1:4000"));
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EndpointPool {
+    endpoints: Vec<Endpoint>,
+    failure_threshold: u32,
+    probe_interval: Duration,
+    state: Mutex<State>,
+}
+
+impl EndpointPool {
+    pub fn new(endpoints: Vec<Endpoint>) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(ConfigError::MissingField("endpoints".to_string()).into());
+        }
+
+        let state = State {
+            health: endpoints.iter().map(|_| Health::default()).collect(),
+            ..Default::default()
+        };
+
+        Ok(Self {
+            endpoints,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            probe_interval: DEFAULT_PROBE_INTERVAL,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Number of consecutive failures after which an endpoint is unhealthy, at least 1.
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Delay between two requests probing an unhealthy endpoint.
+    pub fn probe_interval(mut self, probe_interval: Duration) -> Self {
+        self.probe_interval = probe_interval;
+        self
+    }
+
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// Whether the endpoint with this url is healthy, false for an unknown url.
+    pub fn is_healthy(&self, url: &str) -> bool {
+        let state = self.state.lock().unwrap();
+
+        self.endpoints
+            .iter()
+            .position(|endpoint| endpoint.url == url)
+            .map(|index| state.health[index].unhealthy_since.is_none())
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn url(&self, index: usize) -> &str {
+        &self.endpoints[index].url
+    }
+
+    /// The endpoints to try in order for a new request: an unhealthy endpoint to probe if any,
+    /// the healthy one selected by weight, then the other healthy ones.
+    /// All of them when none is available.
+    pub(crate) fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        // an unhealthy endpoint is half-open once `probe_interval` elapsed: it receives
+        // this request first, and no other one until the probe succeeds or the interval
+        // elapsed again
+        let probe = (0..self.endpoints.len()).find(|&index| {
+            state.health[index]
+                .unhealthy_since
+                .is_some_and(|since| now.duration_since(since) >= self.probe_interval)
+        });
+
+        if let Some(index) = probe {
+            state.health[index].unhealthy_since = Some(now);
+        }
+
+        let available: Vec<usize> = (0..self.endpoints.len())
+            .filter(|&index| state.health[index].unhealthy_since.is_none())
+            .collect();
+
+        if available.is_empty() {
+            return match probe {
+                Some(index) => vec![index],
+                None => (0..self.endpoints.len()).collect(),
+            };
+        }
+
+        let weight = |index: usize| self.endpoints[index].weight as i64;
+
+        let mut candidates = available.clone();
+        candidates.sort_by_key(|&index| Reverse(weight(index)));
+
+        let total: i64 = available.iter().map(|&index| weight(index)).sum();
+
+        if total > 0 {
+            for &index in &available {
+                state.health[index].current_weight += weight(index);
+            }
+
+            let selected = available
+                .iter()
+                .copied()
+                .max_by(|&a, &b| {
+                    let (health_a, health_b) = (&state.health[a], &state.health[b]);
+                    health_a
+                        .current_weight
+                        .cmp(&health_b.current_weight)
+                        .then(b.cmp(&a))
+                })
+                .unwrap();
+
+            state.health[selected].current_weight -= total;
+
+            candidates.retain(|&index| index != selected);
+            candidates.insert(0, selected);
+        }
+
+        if let Some(index) = probe {
+            candidates.insert(0, index);
+        }
+
+        candidates
+    }
+
+    pub(crate) fn record_success(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+
+        let health = &mut state.health[index];
+        health.consecutive_failures = 0;
+        health.unhealthy_since = None;
+    }
+
+    pub(crate) fn record_failure(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+
+        let health = &mut state.health[index];
+        health.consecutive_failures += 1;
+
+        if health.consecutive_failures >= self.failure_threshold {
+            health.unhealthy_since = Some(Instant::now());
+        }
+    }
+
+    /// Remember the endpoint which rendered a report.
+    pub(crate) fn pin(&self, render_id: &RenderId, index: usize) {
+        if self.endpoints.len() == 1 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        if state.pinned_order.len() >= MAX_PINNED_RENDERS {
+            if let Some(oldest) = state.pinned_order.pop_front() {
+                state.pinned.remove(&oldest);
+            }
+        }
+
+        let render_id = render_id.as_str().to_string();
+
+        if state.pinned.insert(render_id.clone(), index).is_none() {
+            state.pinned_order.push_back(render_id);
+        }
+    }
+
+    pub(crate) fn pinned(&self, render_id: &RenderId) -> Option<usize> {
+        let state = self.state.lock().unwrap();
+        state.pinned.get(render_id.as_str()).copied()
+    }
+
+    pub(crate) fn unpin(&self, render_id: &RenderId) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.pinned.remove(render_id.as_str()).is_some() {
+            state
+                .pinned_order
+                .retain(|pinned| pinned != render_id.as_str());
+        }
+    }
+}
@@ -17,6 +17,8 @@ pub enum CarboneError {
     TemplateFileNotFound(String),
     #[error("Carbone SDK error: file {0:?} not found")]
     FileNotFound(String),
+    #[error("Carbone SDK error: file {0:?} already exists")]
+    FileAlreadyExists(String),
     #[error("Carbone SDK {0:?} is a directory")]
     IsADirectory(String),
     #[error("Carbone SDK IoError {0:?}")]
@@ -29,6 +31,46 @@ pub enum CarboneError {
     RequestBodyNotWellFormedJsonError,
     #[error("Carbone SDK {0:?} ParseError {1:?}")]
     ParseError(String, String),
+    #[error("Carbone SDK {0}")]
+    ConfigError(#[from] ConfigError),
+    #[error("Carbone SDK InvalidToken {0:?}")]
+    InvalidToken(String),
+    #[error("Carbone SDK the api token expired at {0} (unix time)")]
+    ExpiredToken(u64),
+    #[error("Carbone SDK the circuit breaker is open, the request was not sent")]
+    CircuitOpen,
+    #[error("Carbone SDK the call was cancelled")]
+    Cancelled,
+    #[error("Carbone SDK api_version {requested:?} is not supported by the Carbone server {server:?}")]
+    UnsupportedApiVersion { requested: String, server: String },
+    #[error("Carbone SDK invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("Carbone SDK no translations for lang {0:?}")]
+    TranslationsNotFound(String),
+}
+
+/// A configuration problem, as opposed to a failure of the Carbone API.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[error("ConfigError: invalid api_url {0:?}")]
+    InvalidUrl(String),
+    #[error("ConfigError: unsupported api_version {0:?}")]
+    UnsupportedApiVersion(String),
+    #[error("ConfigError: api_timeout can not be 0")]
+    ZeroTimeout,
+    #[error("ConfigError: missing field {0:?}")]
+    MissingField(String),
+    #[error("ConfigError: invalid value {1:?} for {0:?}")]
+    InvalidValue(String, String),
+    #[error("ConfigError: unsupported file format {0:?}")]
+    UnsupportedFormat(String),
+    #[error("ConfigError: ParseError {message:?} at line {line} column {column}")]
+    Parse {
+        message: String,
+        line: usize,
+        column: usize,
+    },
 }
 
 impl From<anyhow::Error> for CarboneError {
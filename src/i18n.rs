@@ -0,0 +1,545 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::errors::CarboneError;
+use crate::marker::{extract_text, scan_markers};
+use crate::template::TemplateFile;
+use crate::types::JsonData;
+
+use crate::types::Result;
+
+/// A translatable text of a template, i.e. the `text` of a `{t(text)}` marker.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    /// The translation, none while the text is not translated.
+    pub translation: Option<String>,
+    /// Where the text is used, e.g. `content.xml:1024`.
+    pub references: Vec<String>,
+}
+
+/// The `{t(text)}` texts of a template and their translations in one language,
+/// read from and written to PO or JSON files.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use std::fs;
+///
+/// use carbone_sdk_rs::i18n::Catalog;
+/// use carbone_sdk_rs::template::TemplateFile;
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let template_file = TemplateFile::new("template.odt".to_string(), None)?;
+///
+///     let mut catalog = Catalog::extract(&template_file)?;
+///     catalog.lang = Some("fr-fr".to_string());
+///
+///     fs::write("fr-fr.po", catalog.to_po())?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    /// The language of the translations, e.g. `fr-fr`.
+    pub lang: Option<String>,
+    pub messages: BTreeMap<String, Message>,
+}
+
+impl Catalog {
+    pub fn new<T: Into<String>>(lang: T) -> Self {
+        Self {
+            lang: Some(lang.into()),
+            messages: BTreeMap::new(),
+        }
+    }
+
+    /// Collect the `{t(text)}` texts of a template, untranslated.
+    pub fn extract(template_file: &TemplateFile) -> Result<Self> {
+        let mut catalog = Self::default();
+
+        for (entry, text) in extract_text(&template_file.read_content()?)? {
+            let source = match entry.is_empty() {
+                true => template_file.name().to_string(),
+                false => entry,
+            };
+
+            for (offset, inner) in scan_markers(&text) {
+                let text = match inner.trim().strip_prefix("t(") {
+                    Some(rest) => rest.strip_suffix(')'),
+                    None => None,
+                };
+
+                if let Some(text) = text.filter(|text| !text.is_empty()) {
+                    catalog
+                        .messages
+                        .entry(text.to_string())
+                        .or_default()
+                        .references
+                        .push(format!("{}:{}", source, offset));
+                }
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// Read a catalog from a `.po` or a `.json` file. Without a `Language` header,
+    /// the language is the name of the file, e.g. `fr-fr` for `fr-fr.po`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        let content = fs::read_to_string(path)
+            .or(Err(CarboneError::FileNotFound(path.display().to_string())))?;
+
+        let mut catalog = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("po") => Self::from_po(&content)?,
+            Some("json") => Self::from_json(&content)?,
+            _ => {
+                return Err(CarboneError::ParseError(
+                    path.display().to_string(),
+                    "expected a .po or a .json catalog".to_string(),
+                ))
+            }
+        };
+
+        if catalog.lang.is_none() {
+            catalog.lang = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned());
+        }
+
+        Ok(catalog)
+    }
+
+    /// Parse a gettext PO file. Fuzzy entries are loaded untranslated.
+    pub fn from_po(content: &str) -> Result<Self> {
+        let mut catalog = Self::default();
+
+        for (index, block) in po_blocks(content).into_iter().enumerate() {
+            let msgid = match block.msgid {
+                Some(msgid) => msgid,
+                None => {
+                    return Err(CarboneError::ParseError(
+                        "catalog".to_string(),
+                        format!("entry {} has no msgid", index + 1),
+                    ))
+                }
+            };
+
+            // the header
+            if msgid.is_empty() {
+                catalog.lang = block.msgstr.as_deref().and_then(po_language);
+                continue;
+            }
+
+            let translation = match block.fuzzy {
+                true => None,
+                false => block.msgstr.filter(|msgstr| !msgstr.is_empty()),
+            };
+
+            catalog.messages.insert(
+                msgid,
+                Message {
+                    translation,
+                    references: block.references,
+                },
+            );
+        }
+
+        Ok(catalog)
+    }
+
+    pub fn to_po(&self) -> String {
+        let mut po = String::from("msgid \"\"\nmsgstr \"\"\n");
+
+        if let Some(lang) = &self.lang {
+            po.push_str(&format!("\"Language: {}\\n\"\n", po_escape(lang)));
+        }
+        po.push_str("\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+
+        for (msgid, message) in self.messages.iter() {
+            po.push('\n');
+
+            for reference in message.references.iter() {
+                po.push_str(&format!("#: {}\n", reference));
+            }
+
+            po.push_str(&format!("msgid \"{}\"\n", po_escape(msgid)));
+            po.push_str(&format!(
+                "msgstr \"{}\"\n",
+                po_escape(message.translation.as_deref().unwrap_or(""))
+            ));
+        }
+
+        po
+    }
+
+    /// Parse a JSON object of texts and translations, an empty translation
+    /// meaning the text is not translated.
+    pub fn from_json(content: &str) -> Result<Self> {
+        let parse_error = |e: String| CarboneError::ParseError("catalog".to_string(), e);
+
+        let object: Map<String, Value> =
+            serde_json::from_str(content).map_err(|e| parse_error(e.to_string()))?;
+
+        let mut catalog = Self::default();
+
+        for (text, translation) in object {
+            let translation = match translation {
+                Value::String(translation) => translation,
+                Value::Null => String::new(),
+                _ => {
+                    return Err(parse_error(format!(
+                        "the translation of {:?} is not a string",
+                        text
+                    )))
+                }
+            };
+
+            catalog.messages.insert(
+                text,
+                Message {
+                    translation: Some(translation).filter(|translation| !translation.is_empty()),
+                    references: Vec::new(),
+                },
+            );
+        }
+
+        Ok(catalog)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let object = self
+            .messages
+            .iter()
+            .map(|(text, message)| {
+                let translation = message.translation.clone().unwrap_or_default();
+                (text.clone(), Value::String(translation))
+            })
+            .collect();
+
+        Value::Object(object)
+    }
+
+    /// Keep the translations of a previous catalog for the texts still used,
+    /// e.g. after extracting the catalog of a new version of the template.
+    pub fn merge(&mut self, previous: &Catalog) {
+        if self.lang.is_none() {
+            self.lang = previous.lang.clone();
+        }
+
+        for (text, message) in self.messages.iter_mut() {
+            if message.translation.is_none() {
+                message.translation = previous
+                    .messages
+                    .get(text)
+                    .and_then(|previous| previous.translation.clone());
+            }
+        }
+    }
+
+    /// The texts which are not translated yet.
+    pub fn untranslated(&self) -> Vec<&str> {
+        self.messages
+            .iter()
+            .filter(|(_, message)| message.translation.is_none())
+            .map(|(text, _)| text.as_str())
+            .collect()
+    }
+
+    /// The translated texts, as expected in the `translations` of the render body.
+    pub fn translations(&self) -> Map<String, Value> {
+        self.messages
+            .iter()
+            .filter_map(|(text, message)| {
+                let translation = message.translation.clone()?;
+                Some((text.clone(), Value::String(translation)))
+            })
+            .collect()
+    }
+}
+
+/// The catalogs of a template in several languages, attaching the right
+/// `translations` to a render body.
+///
+/// Given to the client builder or to the `CallOptions` of a render, the translations
+/// of the `lang` of each render body are attached before it is sent.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::i18n::Translations;
+/// use carbone_sdk_rs::template::TemplateId;
+/// use carbone_sdk_rs::types::JsonData;
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_env()?;
+///
+///     // fr-fr.po, de-de.json...
+///     let translations = Translations::load_dir("translations")?;
+///
+///     let carbone = Carbone::builder(&config)
+///         .translations(Arc::new(translations))
+///         .build()?;
+///
+///     let template_id = TemplateId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string())?;
+///     let json_data = JsonData::new(r#"{ "data": {}, "convertTo": "pdf", "lang": "fr-fr" }"#.to_string())?;
+///
+///     let report_content = carbone.generate_report_with_template_id(template_id, json_data).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Translations {
+    catalogs: BTreeMap<String, Catalog>,
+    strict: bool,
+}
+
+impl Translations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail with `CarboneError::TranslationsNotFound` when there is no catalog for
+    /// the `lang` of a render body, instead of sending the body untranslated.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Load the `.po` and `.json` catalogs of a directory.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+
+        if !dir.is_dir() {
+            return Err(CarboneError::FileNotFound(dir.display().to_string()));
+        }
+
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<_>>()?;
+        paths.sort();
+
+        let mut translations = Self::new();
+
+        for path in paths {
+            let is_catalog = matches!(
+                path.extension().and_then(|extension| extension.to_str()),
+                Some("po") | Some("json")
+            );
+
+            if is_catalog && path.is_file() {
+                translations.insert(Catalog::load(&path)?)?;
+            }
+        }
+
+        Ok(translations)
+    }
+
+    /// Add the catalog of a language, replacing the previous one.
+    pub fn insert(&mut self, catalog: Catalog) -> Result<()> {
+        let lang = match catalog.lang.as_deref() {
+            Some(lang) if !lang.trim().is_empty() => lang.trim().to_lowercase(),
+            _ => return Err(CarboneError::EmptyString("lang".to_string())),
+        };
+
+        self.catalogs.insert(lang, catalog);
+        Ok(())
+    }
+
+    pub fn langs(&self) -> Vec<&str> {
+        self.catalogs.keys().map(String::as_str).collect()
+    }
+
+    /// The catalog of a language, falling back to its primary language,
+    /// e.g. the `fr` catalog for `fr-ca`.
+    pub fn get(&self, lang: &str) -> Option<&Catalog> {
+        let lang = lang.trim().to_lowercase();
+
+        self.catalogs.get(&lang).or_else(|| {
+            let primary = lang.split(['-', '_']).next()?;
+            self.catalogs.get(primary)
+        })
+    }
+
+    /// Set the `lang` of a render body and attach the translations of that language,
+    /// keeping the translations already in the body. Without a `lang`, the one
+    /// of the render body is used; a body without any is returned unchanged.
+    ///
+    /// A language without a catalog, e.g. the language the template is written in,
+    /// gets no translations, unless the translations are [`strict`](Self::strict).
+    pub fn apply(&self, json_data: &JsonData, lang: Option<&str>) -> Result<JsonData> {
+        let mut body: Value = serde_json::from_str(json_data.as_str())
+            .or(Err(CarboneError::RequestBodyNotWellFormedJsonError))?;
+
+        let body_object = body
+            .as_object_mut()
+            .ok_or(CarboneError::RequestBodyNotWellFormedJsonError)?;
+
+        let lang = match lang.or(body_object.get("lang").and_then(Value::as_str)) {
+            Some(lang) => lang.to_string(),
+            None => return Ok(json_data.clone()),
+        };
+
+        let catalog = match self.get(&lang) {
+            Some(catalog) => catalog,
+            None if self.strict => return Err(CarboneError::TranslationsNotFound(lang)),
+            None => {
+                body_object.insert("lang".to_string(), Value::String(lang));
+                return JsonData::new(body.to_string());
+            }
+        };
+
+        body_object.insert("lang".to_string(), Value::String(lang.clone()));
+
+        let translations = body_object
+            .entry("translations")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or(CarboneError::RequestBodyNotWellFormedJsonError)?;
+
+        let lang_translations = translations
+            .entry(lang)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or(CarboneError::RequestBodyNotWellFormedJsonError)?;
+
+        lang_translations.extend(catalog.translations());
+
+        JsonData::new(body.to_string())
+    }
+}
+
+#[derive(Default)]
+struct PoBlock {
+    msgid: Option<String>,
+    msgstr: Option<String>,
+    references: Vec<String>,
+    fuzzy: bool,
+}
+
+#[derive(PartialEq)]
+enum PoField {
+    Msgid,
+    Msgstr,
+    Other,
+}
+
+/// Split a PO file into its entries, an entry ending with a blank line.
+fn po_blocks(content: &str) -> Vec<PoBlock> {
+    let mut blocks = Vec::new();
+    let mut block = PoBlock::default();
+    let mut field = PoField::Other;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            if block.msgid.is_some() || block.msgstr.is_some() {
+                blocks.push(std::mem::take(&mut block));
+            }
+            field = PoField::Other;
+            continue;
+        }
+
+        if let Some(reference) = line.strip_prefix("#:") {
+            block
+                .references
+                .extend(reference.split_whitespace().map(String::from));
+        } else if let Some(flags) = line.strip_prefix("#,") {
+            block.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+        } else if line.starts_with('#') {
+            // translator and extracted comments
+        } else if let Some(value) = line.strip_prefix("msgid ") {
+            field = PoField::Msgid;
+            block.msgid = Some(po_unescape(value));
+        } else if let Some(value) = line.strip_prefix("msgstr ") {
+            field = PoField::Msgstr;
+            block.msgstr = Some(po_unescape(value));
+        } else if line.starts_with('"') {
+            let target = match field {
+                PoField::Msgid => block.msgid.as_mut(),
+                PoField::Msgstr => block.msgstr.as_mut(),
+                PoField::Other => None,
+            };
+            if let Some(target) = target {
+                target.push_str(&po_unescape(line));
+            }
+        } else {
+            // msgctxt, msgid_plural and msgstr[n] are not used by Carbone
+            field = PoField::Other;
+        }
+    }
+
+    if block.msgid.is_some() || block.msgstr.is_some() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// The `Language` of a PO header.
+fn po_language(header: &str) -> Option<String> {
+    header.lines().find_map(|line| {
+        let lang = line.strip_prefix("Language:")?.trim();
+        Some(lang.to_string()).filter(|lang| !lang.is_empty())
+    })
+}
+
+fn po_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Decode a quoted PO string, e.g. `"Total \"excl. VAT\""`.
+fn po_unescape(quoted: &str) -> String {
+    let inner = quoted.trim();
+    let inner = inner.strip_prefix('"').unwrap_or(inner);
+    let inner = inner.strip_suffix('"').unwrap_or(inner);
+
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
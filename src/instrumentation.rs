@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::errors::CarboneError;
+use crate::rate_limit::Budget;
+
+/// Counter of the operations, labeled by operation, status, format and error.
+///
+/// The metrics are recorded when the `metrics` feature is enabled, through the
+/// [metrics](https://docs.rs/metrics) facade and whatever exporter is installed.
+///
+/// The labels only take a bounded set of values: `operation`, `status` (`2xx`, `4xx`, `5xx`...
+/// or `none` without response), `format` (the output format of the report, `other` when
+/// unusual) and `error` (the error kind or `none`). Template ids and render ids are never labels.
+pub const METRIC_REQUESTS: &str = "carbone_requests_total";
+/// Histogram of the operation durations in seconds, labeled by operation, status and format.
+pub const METRIC_DURATION: &str = "carbone_request_duration_seconds";
+/// Counter of the bytes sent to the Carbone API, labeled by operation and format.
+pub const METRIC_BYTES_SENT: &str = "carbone_bytes_sent_total";
+/// Counter of the bytes of the reports and templates received from the Carbone API,
+/// labeled by operation and format.
+pub const METRIC_BYTES_RECEIVED: &str = "carbone_bytes_received_total";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    DeleteTemplate,
+    DownloadTemplate,
+    UploadTemplate,
+    Render,
+    GetReport,
+    Status,
+}
+
+impl Operation {
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::DeleteTemplate => "delete_template",
+            Self::DownloadTemplate => "download_template",
+            Self::UploadTemplate => "upload_template",
+            Self::Render => "render",
+            Self::GetReport => "get_report",
+            Self::Status => "status",
+        }
+    }
+
+    pub(crate) fn budget(&self) -> Budget {
+        match self {
+            Self::DeleteTemplate | Self::DownloadTemplate | Self::UploadTemplate | Self::Status => {
+                Budget::Template
+            }
+            Self::Render | Self::GetReport => Budget::Render,
+        }
+    }
+}
+
+/// An operation sent to the Carbone API, with what is known before sending it.
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub(crate) struct Call {
+    pub(crate) operation: Operation,
+    format: &'static str,
+    bytes_sent: u64,
+}
+
+impl Call {
+    pub(crate) fn new(operation: Operation) -> Self {
+        Self {
+            operation,
+            format: "none",
+            bytes_sent: 0,
+        }
+    }
+
+    /// The output format, e.g. the `convertTo` of a render or the extension of a render id.
+    pub(crate) fn format(mut self, format: Option<&str>) -> Self {
+        self.format = format.map(format_label).unwrap_or("none");
+        self
+    }
+
+    /// The `convertTo` of a render body, only parsed when the metrics are recorded.
+    #[allow(unused_mut, unused_variables)]
+    pub(crate) fn render_format(mut self, json_data: &str) -> Self {
+        #[cfg(feature = "metrics")]
+        {
+            let body = serde_json::from_str::<serde_json::Value>(json_data).unwrap_or_default();
+            self = self.format(body.get("convertTo").and_then(|v| v.as_str()));
+        }
+        self
+    }
+
+    pub(crate) fn bytes_sent(mut self, bytes_sent: usize) -> Self {
+        self.bytes_sent = bytes_sent as u64;
+        self
+    }
+
+    /// Record the metrics of the call, a no-op without the `metrics` feature.
+    #[allow(unused_variables)]
+    pub(crate) fn record(
+        &self,
+        duration: Duration,
+        status: Option<StatusCode>,
+        error: Option<&CarboneError>,
+    ) {
+        #[cfg(feature = "metrics")]
+        {
+            let operation = self.operation.as_str();
+            let status_code = status;
+            let status = status_label(status);
+            let error = match (error, status) {
+                (Some(error), _) => error_label(error),
+                (None, _) if status_code == Some(StatusCode::TOO_MANY_REQUESTS) => "rate_limited",
+                (None, "4xx") | (None, "5xx") => "http_status",
+                (None, _) => "none",
+            };
+
+            metrics::counter!(
+                METRIC_REQUESTS,
+                "operation" => operation,
+                "status" => status,
+                "format" => self.format,
+                "error" => error
+            )
+            .increment(1);
+
+            metrics::histogram!(
+                METRIC_DURATION,
+                "operation" => operation,
+                "status" => status,
+                "format" => self.format
+            )
+            .record(duration.as_secs_f64());
+
+            if self.bytes_sent > 0 {
+                metrics::counter!(METRIC_BYTES_SENT, "operation" => operation, "format" => self.format)
+                    .increment(self.bytes_sent);
+            }
+        }
+    }
+
+    /// Record the size of a body once read, the `Content-Length` of a chunked or compressed
+    /// response being unknown or not the read size. A no-op without the `metrics` feature.
+    #[allow(unused_variables)]
+    pub(crate) fn record_bytes_received(&self, bytes_received: usize) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!(
+            METRIC_BYTES_RECEIVED,
+            "operation" => self.operation.as_str(),
+            "format" => self.format
+        )
+        .increment(bytes_received as u64);
+    }
+}
+
+/// Keep the format label bounded: known looking extensions only.
+fn format_label(format: &str) -> &'static str {
+    const FORMATS: [&str; 24] = [
+        "pdf", "docx", "doc", "odt", "xlsx", "xls", "ods", "pptx", "ppt", "odp", "csv", "txt",
+        "html", "xhtml", "xml", "md", "png", "jpg", "jpeg", "svg", "epub", "rtf", "odg", "json",
+    ];
+
+    let format = format.trim().to_ascii_lowercase();
+
+    FORMATS
+        .iter()
+        .find(|known| **known == format)
+        .copied()
+        .unwrap_or("other")
+}
+
+#[cfg(feature = "metrics")]
+fn status_label(status: Option<StatusCode>) -> &'static str {
+    match status.map(|status| status.as_u16() / 100) {
+        Some(1) => "1xx",
+        Some(2) => "2xx",
+        Some(3) => "3xx",
+        Some(4) => "4xx",
+        Some(5) => "5xx",
+        _ => "none",
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn error_label(error: &CarboneError) -> &'static str {
+    match error {
+        CarboneError::RequestError(e) if e.is_connect() => "connect",
+        CarboneError::RequestError(e) if e.is_timeout() => "timeout",
+        CarboneError::RequestError(_) => "request",
+        CarboneError::CircuitOpen => "circuit_open",
+        CarboneError::Cancelled => "cancelled",
+        CarboneError::InvalidToken(_) | CarboneError::ExpiredToken(_) => "auth",
+        CarboneError::ConfigError(_) => "config",
+        _ => "other",
+    }
+}
@@ -1,9 +1,26 @@
 #[cfg(feature = "blocking")]
 pub mod blocking;
+mod archive;
+pub mod auth;
+pub mod cache;
 pub mod carbone;
 pub mod carbone_response;
+pub mod circuit_breaker;
 pub mod config;
+pub mod diff;
+pub mod endpoint;
 pub mod errors;
+pub mod i18n;
+pub mod instrumentation;
+pub mod locale;
+pub mod marker;
+pub mod middleware;
+pub mod options;
+pub mod rate_limit;
 pub mod render;
+pub mod report;
+pub mod sample;
+pub mod sniff;
+pub mod status;
 pub mod template;
 pub mod types;
@@ -0,0 +1,348 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_with::skip_serializing_none;
+
+use crate::errors::{CarboneError, ConfigError};
+use crate::types::JsonData;
+
+use crate::types::Result;
+
+// ISO 4217 active codes, including the funds and the precious metals
+const CURRENCIES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR",
+    "XOF", "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+fn invalid(field: &str, value: &str) -> CarboneError {
+    ConfigError::InvalidValue(field.to_string(), value.to_string()).into()
+}
+
+/// The language of a report, a BCP 47 language tag such as `fr-fr` or `zh-hant-tw`.
+///
+/// The tag is kept lowercase, as expected by Carbone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Lang(String);
+
+impl Lang {
+    pub fn new<T: Into<String>>(lang: T) -> Result<Self> {
+        let lang = lang.into();
+
+        if !is_language_tag(&lang) {
+            return Err(invalid("lang", &lang));
+        }
+
+        Ok(Self(lang.to_lowercase()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Check the syntax of a BCP 47 language tag (RFC 5646), without the grandfathered tags:
+/// `language[-script][-region](-variant)*(-extension)*[-privateuse]` or `privateuse`.
+fn is_language_tag(tag: &str) -> bool {
+    let subtags: Vec<&str> = tag.split('-').collect();
+
+    let well_formed = subtags.iter().all(|subtag| {
+        (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+    if !well_formed {
+        return false;
+    }
+
+    let alpha = |s: &str, len: std::ops::RangeInclusive<usize>| {
+        len.contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphabetic())
+    };
+    let digit = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_digit());
+
+    let mut rest = subtags.as_slice();
+
+    let is_private_use = |rest: &[&str]| rest.len() > 1 && rest[0].eq_ignore_ascii_case("x");
+
+    if is_private_use(rest) {
+        return true;
+    }
+
+    // language, with up to 3 extended language subtags
+    match rest.first() {
+        Some(language) if alpha(language, 2..=3) => {
+            rest = &rest[1..];
+            let mut extlangs = 0;
+            while extlangs < 3 && rest.first().is_some_and(|s| alpha(s, 3..=3)) {
+                rest = &rest[1..];
+                extlangs += 1;
+            }
+        }
+        Some(language) if alpha(language, 5..=8) => rest = &rest[1..],
+        _ => return false,
+    }
+
+    // script
+    if rest.first().is_some_and(|s| alpha(s, 4..=4)) {
+        rest = &rest[1..];
+    }
+
+    // region
+    if rest.first().is_some_and(|s| alpha(s, 2..=2) || digit(s, 3)) {
+        rest = &rest[1..];
+    }
+
+    // variants
+    while let Some(variant) = rest.first() {
+        let starts_with_digit = variant.starts_with(|c: char| c.is_ascii_digit());
+        if variant.len() >= 5 || (variant.len() == 4 && starts_with_digit) {
+            rest = &rest[1..];
+        } else {
+            break;
+        }
+    }
+
+    // extensions, a singleton followed by subtags of 2 to 8 characters
+    while let Some(singleton) = rest.first() {
+        if singleton.len() != 1 || singleton.eq_ignore_ascii_case("x") {
+            break;
+        }
+
+        let len = rest[1..]
+            .iter()
+            .take_while(|subtag| subtag.len() >= 2)
+            .count();
+
+        if len == 0 {
+            return false;
+        }
+        rest = &rest[1 + len..];
+    }
+
+    rest.is_empty() || is_private_use(rest)
+}
+
+/// The timezone of a report, an IANA timezone name such as `Europe/Paris`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Timezone(String);
+
+impl Timezone {
+    pub fn new<T: Into<String>>(timezone: T) -> Result<Self> {
+        let timezone = timezone.into();
+
+        match Tz::from_str(&timezone) {
+            Ok(tz) => Ok(Self(tz.name().to_string())),
+            Err(_) => Err(invalid("timezone", &timezone)),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An ISO 4217 currency code such as `EUR`, kept uppercase.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn new<T: Into<String>>(currency: T) -> Result<Self> {
+        let currency = currency.into();
+        let code = currency.to_ascii_uppercase();
+
+        if !CURRENCIES.contains(&code.as_str()) {
+            return Err(invalid("currency", &currency));
+        }
+
+        Ok(Self(code))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+macro_rules! string_wrapper {
+    ($type:ident) => {
+        impl TryFrom<String> for $type {
+            type Error = CarboneError;
+
+            fn try_from(s: String) -> Result<Self> {
+                Self::new(s)
+            }
+        }
+
+        impl From<$type> for String {
+            fn from(value: $type) -> String {
+                value.0
+            }
+        }
+
+        impl FromStr for $type {
+            type Err = CarboneError;
+
+            fn from_str(s: &str) -> Result<Self> {
+                Self::new(s)
+            }
+        }
+
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+string_wrapper!(Lang);
+string_wrapper!(Timezone);
+string_wrapper!(Currency);
+
+/// The exchange rates used by the currency formatters, relative to the `currencySource`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(try_from = "BTreeMap<Currency, f64>")]
+pub struct CurrencyRates(BTreeMap<Currency, f64>);
+
+impl CurrencyRates {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add the rate of a currency, which must be a positive number.
+    pub fn rate(mut self, currency: Currency, rate: f64) -> Result<Self> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(invalid("currencyRates", &format!("{} {}", currency, rate)));
+        }
+
+        self.0.insert(currency, rate);
+        Ok(self)
+    }
+
+    pub fn get(&self, currency: &Currency) -> Option<f64> {
+        self.0.get(currency).copied()
+    }
+}
+
+// the rates are finite numbers
+impl Eq for CurrencyRates {}
+
+impl TryFrom<BTreeMap<Currency, f64>> for CurrencyRates {
+    type Error = CarboneError;
+
+    fn try_from(rates: BTreeMap<Currency, f64>) -> Result<Self> {
+        rates
+            .into_iter()
+            .try_fold(Self::new(), |rates, (currency, rate)| {
+                rates.rate(currency, rate)
+            })
+    }
+}
+
+/// The locale settings of a render: `lang`, `timezone`, `currencySource`,
+/// `currencyTarget` and `currencyRates`.
+///
+/// The `render_defaults` of the `Config` are added to every render body, unless
+/// the body or the `CallOptions` of the call set them.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::locale::{Currency, Lang, RenderSettings, Timezone};
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let mut config: Config = Default::default();
+///
+///     config.render_defaults = RenderSettings {
+///         lang: Some(Lang::new("fr-fr")?),
+///         timezone: Some(Timezone::new("Europe/Paris")?),
+///         currency_source: Some(Currency::new("EUR")?),
+///         ..Default::default()
+///     };
+///
+///     Ok(())
+/// }
+/// ```
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderSettings {
+    #[serde(default)]
+    pub lang: Option<Lang>,
+    #[serde(default)]
+    pub timezone: Option<Timezone>,
+    #[serde(default, alias = "currency_source")]
+    pub currency_source: Option<Currency>,
+    #[serde(default, alias = "currency_target")]
+    pub currency_target: Option<Currency>,
+    #[serde(default, alias = "currency_rates")]
+    pub currency_rates: Option<CurrencyRates>,
+}
+
+impl RenderSettings {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The settings, completed by the defaults for those which are not set.
+    pub fn or(&self, defaults: &RenderSettings) -> RenderSettings {
+        RenderSettings {
+            lang: self.lang.clone().or_else(|| defaults.lang.clone()),
+            timezone: self.timezone.clone().or_else(|| defaults.timezone.clone()),
+            currency_source: self
+                .currency_source
+                .clone()
+                .or_else(|| defaults.currency_source.clone()),
+            currency_target: self
+                .currency_target
+                .clone()
+                .or_else(|| defaults.currency_target.clone()),
+            currency_rates: self
+                .currency_rates
+                .clone()
+                .or_else(|| defaults.currency_rates.clone()),
+        }
+    }
+
+    /// Add the settings to a render body, keeping those the body already sets.
+    pub fn apply(&self, json_data: &JsonData) -> Result<JsonData> {
+        if self.is_empty() {
+            return Ok(json_data.clone());
+        }
+
+        let mut body: Value = serde_json::from_str(json_data.as_str())
+            .or(Err(CarboneError::RequestBodyNotWellFormedJsonError))?;
+
+        let body_object = body
+            .as_object_mut()
+            .ok_or(CarboneError::RequestBodyNotWellFormedJsonError)?;
+
+        if let Value::Object(settings) = json!(self) {
+            for (key, value) in settings {
+                body_object.entry(key).or_insert(value);
+            }
+        }
+
+        JsonData::new(body.to_string())
+    }
+}
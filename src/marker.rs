@@ -0,0 +1,646 @@
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use zip::ZipArchive;
+
+use crate::errors::CarboneError;
+
+use crate::types::Result;
+
+pub const JSON_SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+
+const NUMBER_FORMATTERS: &[&str] = &[
+    "formatN", "formatC", "formatI", "convCurr", "round", "toFixed", "add", "sub", "mul", "div",
+    "mod", "abs", "ceil", "floor", "int",
+];
+
+const DATE_FORMATTERS: &[&str] = &[
+    "formatD", "convDate", "addD", "subD", "startOfD", "endOfD", "diffD",
+];
+
+const CONDITION_FORMATTERS: &[&str] = &[
+    "ifEQ",
+    "ifNE",
+    "ifGT",
+    "ifGTE",
+    "ifLT",
+    "ifLTE",
+    "ifIN",
+    "ifNIN",
+    "ifEM",
+    "ifNEM",
+    "ifTE",
+    "and",
+    "or",
+    "show",
+    "elseShow",
+    "showBegin",
+    "showEnd",
+    "hideBegin",
+    "hideEnd",
+];
+
+const STRING_FORMATTERS: &[&str] = &[
+    "lowerCase",
+    "upperCase",
+    "ucFirst",
+    "ucWords",
+    "convCRLF",
+    "substr",
+    "padl",
+    "padr",
+    "ellipsis",
+    "prepend",
+    "append",
+    "replace",
+    "split",
+];
+
+/// The root object a marker refers to: `d` for the data, `c` for the complement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MarkerRoot {
+    Data,
+    Complement,
+}
+
+impl MarkerRoot {
+    pub fn as_str(&self) -> &str {
+        match self {
+            MarkerRoot::Data => "d",
+            MarkerRoot::Complement => "c",
+        }
+    }
+}
+
+/// The content of a `[...]` accessor inside a marker path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayAccess {
+    /// A loop iterator such as `[i]` or the end of the loop `[i+1]`.
+    Iterator { name: String, step: u32 },
+    /// A fixed position such as `[0]`.
+    Index(usize),
+    /// Any other accessor, e.g. `[type='invoice']`.
+    Filter(String),
+}
+
+impl ArrayAccess {
+    fn parse(s: &str) -> Self {
+        let s = s.trim();
+
+        if let Ok(index) = s.parse::<usize>() {
+            return ArrayAccess::Index(index);
+        }
+
+        let (name, step) = match s.split_once('+') {
+            Some((name, step)) => (name.trim(), step.trim().parse::<u32>().ok()),
+            None => (s, Some(0)),
+        };
+
+        match step {
+            Some(step) if is_identifier(name) => ArrayAccess::Iterator {
+                name: name.to_string(),
+                step,
+            },
+            _ => ArrayAccess::Filter(s.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(String),
+    Array(ArrayAccess),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formatter {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl Formatter {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        let (name, args) = match s.find('(') {
+            Some(start) => {
+                let end = s.rfind(')')?;
+                if end < start {
+                    return None;
+                }
+                let args = split_top_level(&s[start + 1..end], ',')
+                    .into_iter()
+                    .map(|arg| arg.trim().to_string())
+                    .filter(|arg| !arg.is_empty())
+                    .collect();
+                (s[..start].trim(), args)
+            }
+            None => (s, Vec::new()),
+        };
+
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name: name.to_string(),
+            args,
+        })
+    }
+}
+
+/// Where a marker was found: the zip entry (empty for flat files) and the
+/// byte offset inside the text extracted from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarkerLocation {
+    pub entry: String,
+    pub offset: usize,
+}
+
+/// A `{d.*}` or `{c.*}` marker found in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    pub raw: String,
+    pub root: MarkerRoot,
+    pub path: Vec<PathSegment>,
+    pub formatters: Vec<Formatter>,
+    pub location: MarkerLocation,
+}
+
+impl Marker {
+    /// Parse the content of a marker, without the surrounding braces.
+    ///
+    /// Returns `None` when the content is not a data or complement marker.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rs::marker::{Marker, MarkerLocation, MarkerRoot};
+    ///
+    /// let location = MarkerLocation { entry: "content.xml".to_string(), offset: 0 };
+    /// let marker = Marker::parse("d.products[i].price:formatN(2)", location).unwrap();
+    ///
+    /// assert_eq!(marker.root, MarkerRoot::Data);
+    /// assert_eq!(marker.path_str(), "d.products[i].price");
+    /// ```
+    pub fn parse(s: &str, location: MarkerLocation) -> Option<Self> {
+        let raw = s.trim();
+
+        let mut parts = split_top_level(raw, ':').into_iter();
+        let path = parts.next()?.trim();
+
+        let root = match path.chars().next()? {
+            'd' => MarkerRoot::Data,
+            'c' => MarkerRoot::Complement,
+            _ => return None,
+        };
+
+        let path = parse_path(&path[1..])?;
+
+        let formatters = parts
+            .map(Formatter::parse)
+            .collect::<Option<Vec<Formatter>>>()?;
+
+        Some(Self {
+            raw: raw.to_string(),
+            root,
+            path,
+            formatters,
+            location,
+        })
+    }
+
+    /// The path of the marker without its formatters, e.g. `d.products[i].name`.
+    pub fn path_str(&self) -> String {
+        let mut s = self.root.as_str().to_string();
+
+        for segment in self.path.iter() {
+            match segment {
+                PathSegment::Field(name) => {
+                    s.push('.');
+                    s.push_str(name);
+                }
+                PathSegment::Array(ArrayAccess::Iterator { name, step: 0 }) => {
+                    s.push_str(&format!("[{}]", name));
+                }
+                PathSegment::Array(ArrayAccess::Iterator { name, step }) => {
+                    s.push_str(&format!("[{}+{}]", name, step));
+                }
+                PathSegment::Array(ArrayAccess::Index(index)) => {
+                    s.push_str(&format!("[{}]", index));
+                }
+                PathSegment::Array(ArrayAccess::Filter(filter)) => {
+                    s.push_str(&format!("[{}]", filter));
+                }
+            }
+        }
+
+        s
+    }
+
+    /// Whether the marker is a condition, e.g. `{d.status:ifEQ(paid):show(Paid)}`.
+    pub fn is_condition(&self) -> bool {
+        self.formatters
+            .iter()
+            .any(|formatter| CONDITION_FORMATTERS.contains(&formatter.name.as_str()))
+    }
+
+    /// The loops the marker is part of, e.g. `d.orders[i]` and `d.orders[i].items[j]`
+    /// for `d.orders[i].items[j+1].name`.
+    pub fn loops(&self) -> Vec<String> {
+        let mut loops = Vec::new();
+
+        for (index, segment) in self.path.iter().enumerate() {
+            if let PathSegment::Array(ArrayAccess::Iterator { name, .. }) = segment {
+                let mut prefix = self.path[..index].to_vec();
+                prefix.push(PathSegment::Array(ArrayAccess::Iterator {
+                    name: name.clone(),
+                    step: 0,
+                }));
+
+                let marker = Marker {
+                    path: prefix,
+                    formatters: Vec::new(),
+                    ..self.clone()
+                };
+                loops.push(marker.path_str());
+            }
+        }
+
+        loops
+    }
+
+    /// The formatters of the marker as written in the template, e.g. `formatN(2):prepend('$')`.
+    pub fn formatters_str(&self) -> String {
+        self.formatters
+            .iter()
+            .map(|formatter| match formatter.args.is_empty() {
+                true => formatter.name.clone(),
+                false => format!("{}({})", formatter.name, formatter.args.join(", ")),
+            })
+            .collect::<Vec<String>>()
+            .join(":")
+    }
+
+    /// The type of value the marker expects, inferred from its first typed formatter.
+    pub fn value_type(&self) -> ValueType {
+        for formatter in self.formatters.iter() {
+            let name = formatter.name.as_str();
+
+            if NUMBER_FORMATTERS.contains(&name) {
+                return ValueType::Number;
+            }
+
+            if DATE_FORMATTERS.contains(&name) {
+                // `X` and `x` are the unix timestamp input patterns
+                return match formatter.args.first().map(|a| a.trim_matches('\'')) {
+                    Some("X") | Some("x") if name == "convDate" => ValueType::Number,
+                    _ => ValueType::Date,
+                };
+            }
+
+            if STRING_FORMATTERS.contains(&name) {
+                return ValueType::String;
+            }
+        }
+
+        ValueType::Any
+    }
+}
+
+/// Extract all the data and complement markers of a template.
+///
+/// ODF and OOXML templates are unzipped and the markers are searched in the text
+/// of their XML entries; any other template is searched as plain text.
+pub fn parse_markers(content: &[u8]) -> Result<Vec<Marker>> {
+    let mut markers = Vec::new();
+
+    for (entry, text) in extract_text(content)? {
+        for (offset, inner) in scan_markers(&text) {
+            let location = MarkerLocation {
+                entry: entry.clone(),
+                offset,
+            };
+            if let Some(marker) = Marker::parse(inner, location) {
+                markers.push(marker);
+            }
+        }
+    }
+
+    Ok(markers)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    String,
+    Number,
+    Date,
+    Any,
+}
+
+impl ValueType {
+    fn merge(self, other: ValueType) -> ValueType {
+        match (self, other) {
+            (ValueType::Any, t) | (t, ValueType::Any) => t,
+            (a, b) if a == b => a,
+            _ => ValueType::Any,
+        }
+    }
+
+    fn json_schema(&self) -> Value {
+        match self {
+            ValueType::String => json!({ "type": "string" }),
+            ValueType::Number => json!({ "type": "number" }),
+            // formatD accepts plain dates as well as date-times
+            ValueType::Date => json!({
+                "type": "string",
+                "anyOf": [{ "format": "date" }, { "format": "date-time" }]
+            }),
+            ValueType::Any => json!({ "type": ["string", "number", "boolean", "null"] }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkerNode {
+    Object(BTreeMap<String, MarkerNode>),
+    Array(Box<MarkerNode>),
+    Value(ValueType),
+}
+
+impl MarkerNode {
+    fn insert(&mut self, path: &[PathSegment], value_type: ValueType) {
+        let (segment, rest) = match path.split_first() {
+            Some(s) => s,
+            None => {
+                if let MarkerNode::Value(t) = self {
+                    *t = t.merge(value_type);
+                }
+                return;
+            }
+        };
+
+        match segment {
+            PathSegment::Field(name) => {
+                if let MarkerNode::Value(_) = self {
+                    *self = MarkerNode::Object(BTreeMap::new());
+                }
+                match self {
+                    MarkerNode::Object(children) => children
+                        .entry(name.to_string())
+                        .or_insert(MarkerNode::Value(ValueType::Any))
+                        .insert(rest, value_type),
+                    MarkerNode::Array(item) => item.insert(path, value_type),
+                    MarkerNode::Value(_) => unreachable!(),
+                }
+            }
+            PathSegment::Array(_) => {
+                // an object also accessed as an array becomes an array of these objects,
+                // whatever the order of the markers
+                let item = match std::mem::replace(self, MarkerNode::Value(ValueType::Any)) {
+                    MarkerNode::Array(item) => item,
+                    MarkerNode::Object(children) => Box::new(MarkerNode::Object(children)),
+                    MarkerNode::Value(_) => Box::new(MarkerNode::Value(ValueType::Any)),
+                };
+                *self = MarkerNode::Array(item);
+
+                if let MarkerNode::Array(item) = self {
+                    item.insert(rest, value_type);
+                }
+            }
+        }
+    }
+
+    pub fn json_schema(&self) -> Value {
+        match self {
+            MarkerNode::Object(children) => {
+                let properties: Map<String, Value> = children
+                    .iter()
+                    .map(|(name, node)| (name.to_string(), node.json_schema()))
+                    .collect();
+                json!({ "type": "object", "properties": properties })
+            }
+            MarkerNode::Array(item) => json!({ "type": "array", "items": item.json_schema() }),
+            MarkerNode::Value(value_type) => value_type.json_schema(),
+        }
+    }
+}
+
+/// The shape of the data and complement objects expected by a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerTree {
+    pub data: MarkerNode,
+    pub complement: MarkerNode,
+}
+
+impl MarkerTree {
+    pub fn from_markers(markers: &[Marker]) -> Self {
+        let mut tree = Self {
+            data: MarkerNode::Object(BTreeMap::new()),
+            complement: MarkerNode::Object(BTreeMap::new()),
+        };
+
+        for marker in markers.iter() {
+            let node = match marker.root {
+                MarkerRoot::Data => &mut tree.data,
+                MarkerRoot::Complement => &mut tree.complement,
+            };
+            node.insert(&marker.path, marker.value_type());
+        }
+
+        tree
+    }
+
+    /// Build a JSON Schema (draft 2020-12) describing the render body expected
+    /// by the template: the `data` object and, when used, the `complement` object.
+    pub fn json_schema(&self) -> Value {
+        let mut properties = Map::new();
+        properties.insert("data".to_string(), self.data.json_schema());
+
+        if self.complement != MarkerNode::Object(BTreeMap::new()) {
+            properties.insert("complement".to_string(), self.complement.json_schema());
+        }
+
+        json!({
+            "$schema": JSON_SCHEMA_DRAFT,
+            "type": "object",
+            "properties": properties,
+        })
+    }
+}
+
+/// Return the text of a template per entry: the text of each XML entry
+/// of a zipped template, or the whole content of a flat template.
+pub(crate) fn extract_text(content: &[u8]) -> Result<Vec<(String, String)>> {
+    if !content.starts_with(ZIP_SIGNATURE) {
+        let text = String::from_utf8_lossy(content).into_owned();
+        return Ok(vec![(String::new(), text)]);
+    }
+
+    let parse_error =
+        |e: zip::result::ZipError| CarboneError::ParseError("template".to_string(), e.to_string());
+
+    let mut archive = ZipArchive::new(Cursor::new(content)).map_err(parse_error)?;
+
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.ends_with(".xml"))
+        .map(String::from)
+        .collect();
+    names.sort();
+
+    let mut texts = Vec::new();
+
+    for name in names {
+        let mut xml = String::new();
+        archive
+            .by_name(&name)
+            .map_err(parse_error)?
+            .read_to_string(&mut xml)?;
+        texts.push((name, strip_xml(&xml)));
+    }
+
+    Ok(texts)
+}
+
+/// Remove the XML tags (which can split a marker across several text runs)
+/// and decode the entities.
+fn strip_xml(xml: &str) -> String {
+    let mut text = String::with_capacity(xml.len());
+    let mut in_tag = false;
+
+    for c in xml.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    decode_entities(&text)
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+
+        let c = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => name
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+
+        match (c, entity) {
+            (Some(c), Some((_, end))) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Return the offset and the inner content of every `{...}` in a text.
+pub(crate) fn scan_markers(text: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut open: Option<usize> = None;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => open = Some(i),
+            '}' => {
+                if let Some(start) = open.take() {
+                    found.push((start, &text[start + 1..i]));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    found
+}
+
+fn parse_path(s: &str) -> Option<Vec<PathSegment>> {
+    let mut segments = Vec::new();
+    let mut rest = s.trim();
+
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            let name = r[..end].trim();
+            if name.is_empty() {
+                return None;
+            }
+            segments.push(PathSegment::Field(name.to_string()));
+            rest = &r[end..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']')?;
+            segments.push(PathSegment::Array(ArrayAccess::parse(&r[..end])));
+            rest = &r[end + 1..];
+        } else {
+            return None;
+        }
+    }
+
+    Some(segments)
+}
+
+/// Split on a separator which is neither quoted nor inside parentheses or brackets.
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '(') | (None, '[') => depth += 1,
+            (None, ')') | (None, ']') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&s[start..]);
+    parts
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
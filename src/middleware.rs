@@ -0,0 +1,77 @@
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Request, StatusCode, Url};
+
+use crate::types::Result;
+
+/// What a middleware sees of a response, the body is left to the client.
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    pub method: Method,
+    pub url: Url,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// Time between sending the request and receiving the response headers.
+    pub elapsed: Duration,
+}
+
+/// A hook run for every request sent to the Carbone API, by the async and the blocking clients.
+///
+/// The middlewares run in the order they were added to the client builder, after the
+/// authentication header is set. An error returned by `on_request` aborts the request.
+///
+/// The blocking client hands a copy of its request, the method, url, headers, timeout
+/// and the body (unless it is a multipart form) are copied back once the middlewares ran.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use reqwest::header::HeaderValue;
+/// use reqwest::Request;
+///
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::middleware::{Middleware, ResponseMeta};
+///
+/// #[derive(Debug)]
+/// struct Gateway;
+///
+/// impl Middleware for Gateway {
+///     fn on_request(&self, request: &mut Request) -> Result<(), CarboneError> {
+///         request
+///             .headers_mut()
+///             .insert("x-gateway-key", HeaderValue::from_static("secret"));
+///         Ok(())
+///     }
+///
+///     fn on_response(&self, response: &ResponseMeta) {
+///         println!("{} {} {:?}", response.method, response.status, response.elapsed);
+///     }
+/// }
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_env()?;
+///
+///     let carbone = Carbone::builder(&config)
+///         .middleware(Arc::new(Gateway))
+///         .build()?;
+///
+///     Ok(())
+/// }
+/// ```
+pub trait Middleware: Send + Sync + fmt::Debug {
+    fn on_request(&self, request: &mut Request) -> Result<()> {
+        let _ = request;
+        Ok(())
+    }
+
+    fn on_response(&self, response: &ResponseMeta) {
+        let _ = response;
+    }
+}
@@ -0,0 +1,127 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::CarboneError;
+use crate::i18n::Translations;
+use crate::locale::RenderSettings;
+
+use crate::types::Result;
+
+/// Options of a single call, given to the `_with` variants of the client methods.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::env;
+/// use std::time::Duration;
+///
+/// use tokio_util::sync::CancellationToken;
+///
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::options::CallOptions;
+/// use carbone_sdk_rs::template::TemplateId;
+/// use carbone_sdk_rs::types::{ApiJsonToken, JsonData};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_env()?;
+///     let api_token = ApiJsonToken::new(env::var("CARBONE_TOKEN").unwrap())?;
+///
+///     let carbone = Carbone::new(&config, &api_token)?;
+///
+///     let template_id = TemplateId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string())?;
+///     let json_data = JsonData::new(r#"{ "data": {}, "convertTo": "pdf" }"#.to_string())?;
+///
+///     let cancellation_token = CancellationToken::new();
+///
+///     let options = CallOptions::new()
+///         .timeout(Duration::from_secs(600))
+///         .cancellation_token(cancellation_token.clone());
+///
+///     // e.g. cancelled when the user leaves the page
+///     let report_content = carbone
+///         .generate_report_with_template_id_with(template_id, json_data, &options)
+///         .await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) headers: HeaderMap,
+    cancellation_token: Option<CancellationToken>,
+    pub(crate) render_settings: RenderSettings,
+    pub(crate) translations: Option<Arc<Translations>>,
+}
+
+impl CallOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Override the `api_timeout` of the config for each request of the call.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Add a header to each request of the call.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Abort the call with `CarboneError::Cancelled` once the token is cancelled.
+    ///
+    /// The blocking client only checks the token before sending each request.
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Override the `render_defaults` of the config for the render of the call,
+    /// the settings of the render body still taking precedence.
+    pub fn render_settings(mut self, render_settings: RenderSettings) -> Self {
+        self.render_settings = render_settings;
+        self
+    }
+
+    /// Override the translations of the client for the render of the call.
+    pub fn translations(mut self, translations: Arc<Translations>) -> Self {
+        self.translations = Some(translations);
+        self
+    }
+
+    #[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+    pub(crate) fn check_cancelled(&self) -> Result<()> {
+        match &self.cancellation_token {
+            Some(token) if token.is_cancelled() => Err(CarboneError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run the future until it completes or the call is cancelled.
+    pub(crate) async fn cancellable<T, F>(&self, future: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        match &self.cancellation_token {
+            Some(token) => {
+                tokio::select! {
+                    biased;
+                    _ = token.cancelled() => Err(CarboneError::Cancelled),
+                    result = future => result,
+                }
+            }
+            None => future.await,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+pub const HEADER_RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+pub const HEADER_RATE_LIMIT_RESET: &str = "x-ratelimit-reset";
+
+// a reset above this value is a unix time, below it a number of seconds
+const RESET_UNIX_TIME_MIN: u64 = 1_000_000_000;
+
+/// A number of requests allowed per period, all of them can be sent in a burst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, per: Duration) -> Self {
+        Self { requests, per }
+    }
+
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    fn capacity(&self) -> f64 {
+        self.requests.max(1) as f64
+    }
+
+    // permits per second
+    fn rate(&self) -> f64 {
+        self.capacity() / self.per.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// The endpoints sharing a budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Budget {
+    /// `/render`
+    Render,
+    /// `/template`
+    Template,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    limit: RateLimit,
+    // negative when permits are reserved ahead
+    tokens: f64,
+    // in the future when the API asked to wait
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.capacity(),
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if now > self.updated_at {
+            let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.limit.rate()).min(self.limit.capacity());
+            self.updated_at = now;
+        }
+    }
+
+    /// Take a permit, returns how long to wait before using it.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+
+        self.tokens -= 1.0;
+
+        let blocked = self.updated_at.saturating_duration_since(now);
+
+        if self.tokens >= 0.0 {
+            blocked
+        } else {
+            blocked + Duration::from_secs_f64(-self.tokens / self.limit.rate())
+        }
+    }
+
+    fn block_until(&mut self, now: Instant, until: Instant) {
+        self.refill(now);
+
+        if until > self.updated_at {
+            self.updated_at = until;
+            self.tokens = self.tokens.min(0.0);
+        }
+    }
+
+    fn update(&mut self, headers: &HeaderMap, too_many_requests: bool) {
+        let now = Instant::now();
+
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+
+        if let Some(remaining) = header(HEADER_RATE_LIMIT_REMAINING) {
+            self.refill(now);
+            self.tokens = self.tokens.min(remaining as f64);
+
+            if remaining == 0 {
+                if let Some(reset) = header(HEADER_RATE_LIMIT_RESET) {
+                    self.block_until(now, now + reset_delay(reset));
+                }
+            }
+        }
+
+        if too_many_requests {
+            let retry_after = header(RETRY_AFTER.as_str())
+                .map(Duration::from_secs)
+                .unwrap_or_else(|| Duration::from_secs_f64(1.0 / self.limit.rate()));
+
+            self.block_until(now, now + retry_after);
+        }
+    }
+}
+
+fn reset_delay(reset: u64) -> Duration {
+    if reset < RESET_UNIX_TIME_MIN {
+        return Duration::from_secs(reset);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    Duration::from_secs(reset.saturating_sub(now))
+}
+
+/// A token bucket rate limiter, with a budget for the render endpoints
+/// and another one for the template endpoints.
+///
+/// The requests wait for a permit instead of failing. The `X-RateLimit-Remaining`,
+/// `X-RateLimit-Reset` and `Retry-After` headers of the responses adjust the budgets,
+/// and a request answered with `429 Too Many Requests` is sent again once allowed.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::rate_limit::{RateLimit, RateLimiter};
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config = Config::from_env()?;
+///
+///     let rate_limiter = RateLimiter::new()
+///         .render(RateLimit::per_minute(60))
+///         .template(RateLimit::per_minute(10));
+///
+///     let carbone = Carbone::builder(&config)
+///         .rate_limiter(Arc::new(rate_limiter))
+///         .build()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    render: Option<Mutex<Bucket>>,
+    template: Option<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// A rate limiter without limit until the budgets are set.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn render(mut self, limit: RateLimit) -> Self {
+        self.render = Some(Mutex::new(Bucket::new(limit)));
+        self
+    }
+
+    pub fn template(mut self, limit: RateLimit) -> Self {
+        self.template = Some(Mutex::new(Bucket::new(limit)));
+        self
+    }
+
+    fn bucket(&self, budget: Budget) -> Option<&Mutex<Bucket>> {
+        match budget {
+            Budget::Render => self.render.as_ref(),
+            Budget::Template => self.template.as_ref(),
+        }
+    }
+
+    /// Take a permit, returns how long to wait before sending the request.
+    pub(crate) fn reserve(&self, budget: Budget) -> Duration {
+        match self.bucket(budget) {
+            Some(bucket) => bucket.lock().unwrap().reserve(Instant::now()),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Adjust the budget from the rate limit headers of a response.
+    pub(crate) fn update(&self, budget: Budget, headers: &HeaderMap, too_many_requests: bool) {
+        if let Some(bucket) = self.bucket(budget) {
+            bucket.lock().unwrap().update(headers, too_many_requests);
+        }
+    }
+}
@@ -0,0 +1,541 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, CONTENT_DISPOSITION, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use sha2::{Digest, Sha256};
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::errors::CarboneError;
+use crate::render::RenderId;
+use crate::template::TemplateId;
+use crate::types::JsonData;
+
+use crate::types::Result;
+
+/// Name of the manifest written at the end of a `ReportArchive`.
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// A generated report and what is known about how it was rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub render_id: RenderId,
+    /// The template rendered, known when the report comes from `render_report`.
+    pub template_id: Option<TemplateId>,
+    /// The sha256 of the render body, known when the report comes from `render_report`.
+    pub data_hash: Option<String>,
+    /// The file name sent in the Content-Disposition header, set from the `reportName` of the render.
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub content: Bytes,
+}
+
+impl Report {
+    pub(crate) fn from_response(render_id: RenderId, headers: &HeaderMap, content: Bytes) -> Self {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+        Self {
+            render_id,
+            template_id: None,
+            data_hash: None,
+            file_name: header(CONTENT_DISPOSITION).and_then(content_disposition_file_name),
+            content_type: header(CONTENT_TYPE).map(String::from),
+            content,
+        }
+    }
+
+    /// A safe file name for the report: the file name sent by Carbone or the render id,
+    /// without their directories, else the sha256 of the render id. The name always ends
+    /// with the extension of the render id, e.g. `invoice.pdf` for a `reportName` of `invoice`.
+    pub fn name(&self) -> String {
+        let render_id = self.render_id.as_str();
+
+        let name = self
+            .file_name
+            .as_deref()
+            .and_then(sanitize_file_name)
+            .or_else(|| sanitize_file_name(render_id))
+            .unwrap_or_else(|| sha256(render_id.as_bytes()));
+
+        match render_id_extension(render_id) {
+            Some(extension) if !has_extension(&name, extension) => {
+                format!("{}.{}", name, extension)
+            }
+            _ => name,
+        }
+    }
+
+    /// Write the report into a directory, created if needed, under its [`name`](Self::name),
+    /// replacing an existing file. Returns the path of the file.
+    ///
+    /// The file is written next to its destination and then renamed, so that
+    /// the destination never holds a partially written report.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rs::carbone::Carbone;
+    /// use carbone_sdk_rs::config::Config;
+    /// use carbone_sdk_rs::errors::CarboneError;
+    /// use carbone_sdk_rs::render::RenderId;
+    /// use carbone_sdk_rs::types::ApiJsonToken;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let config: Config = Default::default();
+    ///     let api_token = ApiJsonToken::new(env::var("CARBONE_TOKEN").unwrap())?;
+    ///     let carbone = Carbone::new(&config, &api_token)?;
+    ///
+    ///     let render_id = RenderId::new("MTAuMjAuMjEuMTAgICAg01E98H4R7PMC2H6XSE5Z6J8XYQ.pdf")?;
+    ///     let report = carbone.fetch_report(&render_id).await?;
+    ///
+    ///     let path = report.save_to("reports")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn save_to<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf> {
+        self.save_to_with(dir, OnConflict::Overwrite)
+    }
+
+    /// Write the report into a directory, see [`save_to`](Self::save_to), doing
+    /// what `on_conflict` says when a file already has its name.
+    pub fn save_to_with<P: AsRef<Path>>(&self, dir: P, on_conflict: OnConflict) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        write_atomic(&dir.join(self.name()), &self.content, on_conflict)
+    }
+
+    /// Write the report to a file, or into a directory when the path is an existing one.
+    pub(crate) fn save(&self, path: &Path, on_conflict: OnConflict) -> Result<PathBuf> {
+        if path.is_dir() {
+            self.save_to_with(path, on_conflict)
+        } else {
+            write_atomic(path, &self.content, on_conflict)
+        }
+    }
+
+    /// The sha256 of the content of the report.
+    pub fn hash(&self) -> String {
+        sha256(&self.content)
+    }
+}
+
+/// What to do when saving a report to a file which already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Replace the existing file.
+    #[default]
+    Overwrite,
+    /// Keep the existing file and fail with `CarboneError::FileAlreadyExists`.
+    Fail,
+    /// Keep the existing file and save the report with a `-2`, `-3`... suffix.
+    Rename,
+}
+
+// distinguishes the temporary files of the threads of a process
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Write a file through a temporary file in the same directory, renamed once complete.
+pub(crate) fn write_atomic(
+    path: &Path,
+    content: &[u8],
+    on_conflict: OnConflict,
+) -> Result<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| CarboneError::IsADirectory(path.display().to_string()))?;
+
+    if path.is_dir() {
+        return Err(CarboneError::IsADirectory(path.display().to_string()));
+    }
+
+    let temp_path = dir.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = write_file(&temp_path, content).and_then(|_| match on_conflict {
+        OnConflict::Overwrite => {
+            fs::rename(&temp_path, path)?;
+            Ok(path.to_path_buf())
+        }
+        OnConflict::Fail => persist_new(&temp_path, path),
+        OnConflict::Rename => {
+            let (stem, extension) = split_extension(&file_name);
+            let mut n = 1;
+
+            loop {
+                let candidate = match n {
+                    1 => path.to_path_buf(),
+                    n => dir.join(format!("{}-{}{}", stem, n, extension)),
+                };
+
+                match persist_new(&temp_path, &candidate) {
+                    Err(CarboneError::FileAlreadyExists(_)) => n += 1,
+                    result => break result,
+                }
+            }
+        }
+    });
+
+    // the temporary file is left when the rename failed, or after a hard link
+    let _ = fs::remove_file(&temp_path);
+
+    result
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Move the temporary file to the path, unless a file already exists there.
+fn persist_new(temp_path: &Path, path: &Path) -> Result<PathBuf> {
+    // a hard link fails atomically when the path exists
+    match fs::hard_link(temp_path, path) {
+        Ok(()) => Ok(path.to_path_buf()),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            Err(CarboneError::FileAlreadyExists(path.display().to_string()))
+        }
+        // file systems without hard links
+        Err(_) if path.exists() => Err(CarboneError::FileAlreadyExists(path.display().to_string())),
+        Err(_) => {
+            fs::rename(temp_path, path)?;
+            Ok(path.to_path_buf())
+        }
+    }
+}
+
+/// Split a file name into its stem and its extension, the dot included.
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(i) if i > 0 => (&name[..i], &name[i..]),
+        _ => (name, ""),
+    }
+}
+
+/// The extension of a render id, when it is a plain one such as `pdf`.
+fn render_id_extension(render_id: &str) -> Option<&str> {
+    render_id
+        .rsplit_once('.')
+        .map(|(_, extension)| extension)
+        .filter(|extension| !extension.is_empty())
+        .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+fn has_extension(name: &str, extension: &str) -> bool {
+    let (_, name_extension) = split_extension(name);
+    name_extension
+        .strip_prefix('.')
+        .is_some_and(|name_extension| name_extension.eq_ignore_ascii_case(extension))
+}
+
+/// The sha256 of a render body, as listed in the manifest of a `ReportArchive`.
+pub fn data_hash(json_data: &JsonData) -> String {
+    sha256(json_data.as_str().as_bytes())
+}
+
+pub(crate) fn sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// The file name of a Content-Disposition header, preferring the UTF-8 `filename*`.
+fn content_disposition_file_name(value: &str) -> Option<String> {
+    let mut file_name = None;
+
+    for param in split_params(value) {
+        let (name, value) = match param.split_once('=') {
+            Some((name, value)) => (name.trim().to_ascii_lowercase(), value.trim()),
+            None => continue,
+        };
+
+        match name.as_str() {
+            "filename*" => {
+                // charset'language'percent-encoded
+                let mut parts = value.splitn(3, '\'');
+                let charset = parts.next().unwrap_or("");
+                let encoded = parts.nth(1);
+
+                if let Some(encoded) = encoded.filter(|_| charset.eq_ignore_ascii_case("utf-8")) {
+                    if let Some(decoded) = percent_decode(encoded) {
+                        return Some(decoded);
+                    }
+                }
+            }
+            "filename" => file_name = Some(unquote(value)),
+            _ => {}
+        }
+    }
+
+    file_name
+}
+
+/// Split the parameters of a header value on the `;` outside of quotes.
+fn split_params(value: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                params.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    params.push(&value[start..]);
+    params
+}
+
+fn unquote(value: &str) -> String {
+    let inner = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner,
+        None => return value.to_string(),
+    };
+
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+
+    unquoted
+}
+
+fn percent_decode(encoded: &str) -> Option<String> {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = encoded.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// Keep the last component of a file name, without control characters.
+fn sanitize_file_name(file_name: &str) -> Option<String> {
+    let name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let name = name.trim().trim_start_matches('.').trim();
+
+    Some(name.to_string()).filter(|name| !name.is_empty())
+}
+
+/// The content of the `manifest.json` of a `ReportArchive`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Manifest {
+    pub reports: Vec<ManifestReport>,
+    pub failures: Vec<ManifestFailure>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestReport {
+    /// The name of the entry in the archive.
+    pub name: String,
+    pub render_id: String,
+    #[serde(default)]
+    pub template_id: Option<String>,
+    #[serde(default)]
+    pub data_hash: Option<String>,
+    pub output_hash: String,
+    pub size: u64,
+}
+
+/// A render of the batch which failed, and so has no entry in the archive.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFailure {
+    #[serde(default)]
+    pub template_id: Option<String>,
+    #[serde(default)]
+    pub data_hash: Option<String>,
+    pub error: String,
+}
+
+/// Write the reports of a batch into a single ZIP archive, each report being written
+/// as soon as it is added, followed by a `manifest.json` listing the reports and the failures.
+///
+/// The entries are named from the file name of the reports (see [`Report::name`]),
+/// a name already used getting a `-2`, `-3`... suffix.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use std::env;
+/// use std::fs::File;
+///
+/// use carbone_sdk_rs::carbone::Carbone;
+/// use carbone_sdk_rs::config::Config;
+/// use carbone_sdk_rs::errors::CarboneError;
+/// use carbone_sdk_rs::report::ReportArchive;
+/// use carbone_sdk_rs::template::TemplateId;
+/// use carbone_sdk_rs::types::{ApiJsonToken, JsonData};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///     let api_token = ApiJsonToken::new(env::var("CARBONE_TOKEN").unwrap())?;
+///     let carbone = Carbone::new(&config, &api_token)?;
+///
+///     let template_id = TemplateId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114")?;
+///
+///     let mut archive = ReportArchive::new(File::create("invoices.zip")?);
+///
+///     for id in 1..=3 {
+///         let body = format!(r#"{{ "data": {{ "id": {} }}, "convertTo": "pdf", "reportName": "invoice-{{d.id}}.pdf" }}"#, id);
+///         let json_data = JsonData::new(body)?;
+///
+///         match carbone.render_report(template_id.clone(), json_data.clone()).await {
+///             Ok(report) => {
+///                 archive.add(&report)?;
+///             }
+///             Err(e) => archive.add_failure(Some(&template_id), Some(&json_data), &e),
+///         }
+///     }
+///
+///     archive.finish()?;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct ReportArchive<W: Write + Seek> {
+    writer: ZipWriter<W>,
+    names: HashSet<String>,
+    manifest: Manifest,
+}
+
+impl<W: Write + Seek> ReportArchive<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: ZipWriter::new(writer),
+            names: HashSet::new(),
+            manifest: Manifest::default(),
+        }
+    }
+
+    /// Write a report into the archive, returning the name of its entry.
+    pub fn add(&mut self, report: &Report) -> Result<String> {
+        let name = self.unique_name(&report.name());
+
+        self.writer
+            .start_file(name.as_str(), FileOptions::default())
+            .map_err(zip_error)?;
+        self.writer.write_all(&report.content)?;
+
+        self.manifest.reports.push(ManifestReport {
+            name: name.clone(),
+            render_id: report.render_id.as_str().to_string(),
+            template_id: report
+                .template_id
+                .as_ref()
+                .map(|template_id| template_id.as_str().to_string()),
+            data_hash: report.data_hash.clone(),
+            output_hash: report.hash(),
+            size: report.content.len() as u64,
+        });
+
+        Ok(name)
+    }
+
+    /// List a render which failed in the manifest.
+    pub fn add_failure(
+        &mut self,
+        template_id: Option<&TemplateId>,
+        json_data: Option<&JsonData>,
+        error: &CarboneError,
+    ) {
+        self.manifest.failures.push(ManifestFailure {
+            template_id: template_id.map(|template_id| template_id.as_str().to_string()),
+            data_hash: json_data.map(data_hash),
+            error: error.to_string(),
+        });
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Write the manifest and the end of the archive, returning the writer.
+    pub fn finish(mut self) -> Result<W> {
+        let manifest = serde_json::to_vec_pretty(&self.manifest)
+            .map_err(|e| CarboneError::Error(e.to_string()))?;
+
+        self.writer
+            .start_file(MANIFEST_NAME, FileOptions::default())
+            .map_err(zip_error)?;
+        self.writer.write_all(&manifest)?;
+
+        self.writer.finish().map_err(zip_error)
+    }
+
+    /// The name, or the name with a `-2`, `-3`... suffix if it is already used,
+    /// ignoring the case for the file systems which do.
+    fn unique_name(&mut self, name: &str) -> String {
+        let (stem, extension) = split_extension(name);
+
+        let mut candidate = name.to_string();
+        let mut n = 2;
+
+        while self.names.contains(&candidate.to_lowercase())
+            || candidate.eq_ignore_ascii_case(MANIFEST_NAME)
+        {
+            candidate = format!("{}-{}{}", stem, n, extension);
+            n += 1;
+        }
+
+        self.names.insert(candidate.to_lowercase());
+        candidate
+    }
+}
+
+fn zip_error(e: ZipError) -> CarboneError {
+    match e {
+        ZipError::Io(e) => CarboneError::IoError(e),
+        e => CarboneError::Error(e.to_string()),
+    }
+}
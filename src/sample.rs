@@ -0,0 +1,207 @@
+use serde_json::{json, Map, Value};
+
+use crate::marker::{MarkerNode, MarkerTree, ValueType};
+use crate::types::JsonData;
+
+use crate::types::Result;
+
+const FIRST_NAMES: &[&str] = &[
+    "John", "Jane", "Alice", "Bob", "Emma", "Lucas", "Chloe", "Noah",
+];
+const LAST_NAMES: &[&str] = &[
+    "Wick", "Doe", "Martin", "Smith", "Bernard", "Dubois", "Moreau",
+];
+const CITIES: &[&str] = &[
+    "Paris", "Lyon", "Berlin", "Madrid", "Montreal", "Nantes", "Geneva",
+];
+const STREETS: &[&str] = &[
+    "Main Street",
+    "Rue de la Paix",
+    "Station Road",
+    "Park Avenue",
+];
+const COMPANIES: &[&str] = &[
+    "Acme",
+    "Globex",
+    "Initech",
+    "Umbrella",
+    "Hooli",
+    "Stark Industries",
+];
+const COUNTRIES: &[&str] = &["France", "Germany", "Spain", "Canada", "Switzerland"];
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+];
+
+/// Generate fake render data from the markers of a template,
+/// e.g. to preview a template without real data.
+///
+/// The same seed always produces the same data.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rs::sample::SampleDataGenerator;
+/// use carbone_sdk_rs::template::TemplateFile;
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+///
+///     let generator = SampleDataGenerator::new(42);
+///     let json_data = generator.generate(&template_file.marker_tree()?)?;
+///
+///     assert_eq!(json_data.as_str().is_empty(), false);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SampleDataGenerator {
+    pub seed: u64,
+    /// Number of elements generated for each array (loop) of the template.
+    pub array_len: usize,
+    /// The `convertTo` of the generated render body, none to keep the template format.
+    pub convert_to: Option<String>,
+}
+
+impl SampleDataGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            array_len: 3,
+            convert_to: Some("pdf".to_string()),
+        }
+    }
+
+    /// Generate a render body which can be given to `render_data` or
+    /// `generate_report_with_template_id`.
+    pub fn generate(&self, tree: &MarkerTree) -> Result<JsonData> {
+        JsonData::new(self.generate_value(tree).to_string())
+    }
+
+    pub fn generate_value(&self, tree: &MarkerTree) -> Value {
+        let mut rng = SplitMix64(self.seed);
+
+        let mut body = Map::new();
+        body.insert(
+            "data".to_string(),
+            self.node_value(&tree.data, "", &mut rng),
+        );
+
+        if let MarkerNode::Object(children) = &tree.complement {
+            if !children.is_empty() {
+                let complement = self.node_value(&tree.complement, "", &mut rng);
+                body.insert("complement".to_string(), complement);
+            }
+        }
+
+        if let Some(convert_to) = &self.convert_to {
+            body.insert("convertTo".to_string(), json!(convert_to));
+        }
+
+        Value::Object(body)
+    }
+
+    fn node_value(&self, node: &MarkerNode, key: &str, rng: &mut SplitMix64) -> Value {
+        match node {
+            MarkerNode::Object(children) => Value::Object(
+                children
+                    .iter()
+                    .map(|(name, child)| (name.to_string(), self.node_value(child, name, rng)))
+                    .collect(),
+            ),
+            MarkerNode::Array(item) => Value::Array(
+                (0..self.array_len)
+                    .map(|_| self.node_value(item, key, rng))
+                    .collect(),
+            ),
+            MarkerNode::Value(ValueType::Number) => {
+                json!((rng.below(100_000) as f64) / 100.0)
+            }
+            MarkerNode::Value(ValueType::Date) => json!(format!(
+                "{}-{:02}-{:02}T{:02}:{:02}:00Z",
+                2020 + rng.below(5),
+                1 + rng.below(12),
+                1 + rng.below(28),
+                rng.below(24),
+                rng.below(60)
+            )),
+            MarkerNode::Value(_) => json!(fake_string(key, rng)),
+        }
+    }
+}
+
+/// Return a string matching the name of the field when it is a well known one.
+fn fake_string(key: &str, rng: &mut SplitMix64) -> String {
+    let key = key.to_lowercase();
+
+    if key.contains("firstname") {
+        rng.pick(FIRST_NAMES).to_string()
+    } else if key.contains("lastname") {
+        rng.pick(LAST_NAMES).to_string()
+    } else if key.contains("email") {
+        format!(
+            "{}.{}@example.com",
+            rng.pick(FIRST_NAMES).to_lowercase(),
+            rng.pick(LAST_NAMES).to_lowercase()
+        )
+    } else if key.contains("company") {
+        rng.pick(COMPANIES).to_string()
+    } else if key.contains("name") {
+        format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES))
+    } else if key.contains("city") {
+        rng.pick(CITIES).to_string()
+    } else if key.contains("country") {
+        rng.pick(COUNTRIES).to_string()
+    } else if key.contains("address") || key.contains("street") {
+        format!("{} {}", 1 + rng.below(200), rng.pick(STREETS))
+    } else if key.contains("phone") {
+        format!(
+            "+33 6 {:02} {:02} {:02} {:02}",
+            rng.below(100),
+            rng.below(100),
+            rng.below(100),
+            rng.below(100)
+        )
+    } else {
+        let len = 2 + rng.below(3) as usize;
+        (0..len)
+            .map(|_| rng.pick(WORDS))
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+/// Small deterministic pseudo random number generator.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn pick<'a>(&mut self, values: &[&'a str]) -> &'a str {
+        values[self.below(values.len() as u64) as usize]
+    }
+}
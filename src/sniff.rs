@@ -0,0 +1,262 @@
+use std::io::{self, Cursor, Read};
+
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::errors::CarboneError;
+
+use crate::types::Result;
+
+/// Largest template accepted before uploading it, in bytes.
+pub const MAX_TEMPLATE_SIZE: usize = 20 * 1024 * 1024;
+/// Largest decompressed content of an ODF or OOXML template, in bytes.
+pub const MAX_DECOMPRESSED_SIZE: u64 = 200 * 1024 * 1024;
+
+// an entry compressed more than this is a zip bomb, unless small
+const MAX_COMPRESSION_RATIO: u64 = 100;
+const MIN_SUSPICIOUS_SIZE: u64 = 1024 * 1024;
+
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+// OLE compound file: legacy binary formats (doc, xls, ppt) and encrypted OOXML documents
+const OLE_SIGNATURE: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// The format of a template, detected from its content rather than its file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateFormat {
+    Odt,
+    Ods,
+    Odp,
+    Odg,
+    Docx,
+    Xlsx,
+    Pptx,
+    Html,
+    Xml,
+    Text,
+}
+
+impl TemplateFormat {
+    /// Detect the format of a template and check it can be uploaded: it must be
+    /// an ODF, OOXML, HTML, XML or text document, not corrupt, not password-protected,
+    /// not larger than `MAX_TEMPLATE_SIZE` and, for an archive, not decompressing
+    /// to more than `MAX_DECOMPRESSED_SIZE`.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::fs;
+    ///
+    /// use carbone_sdk_rs::errors::CarboneError;
+    /// use carbone_sdk_rs::sniff::TemplateFormat;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let content = fs::read("tests/data/template.odt")?;
+    ///     let format = TemplateFormat::sniff(&content)?;
+    ///
+    ///     assert_eq!(format, TemplateFormat::Odt);
+    ///     assert_eq!(format.mime_type(), "application/vnd.oasis.opendocument.text");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sniff(content: &[u8]) -> Result<Self> {
+        if content.is_empty() {
+            return Err(invalid("the template is empty"));
+        }
+
+        if content.len() > MAX_TEMPLATE_SIZE {
+            return Err(invalid(format!(
+                "the template is {} bytes, more than the {} bytes allowed",
+                content.len(),
+                MAX_TEMPLATE_SIZE
+            )));
+        }
+
+        if content.starts_with(ZIP_SIGNATURE) {
+            return sniff_zip(content);
+        }
+
+        if content.starts_with(OLE_SIGNATURE) {
+            return Err(invalid(
+                "the template is password-protected or a legacy binary document (doc, xls, ppt)",
+            ));
+        }
+
+        sniff_text(content)
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Odt => "application/vnd.oasis.opendocument.text",
+            Self::Ods => "application/vnd.oasis.opendocument.spreadsheet",
+            Self::Odp => "application/vnd.oasis.opendocument.presentation",
+            Self::Odg => "application/vnd.oasis.opendocument.graphics",
+            Self::Docx => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            Self::Pptx => {
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+            }
+            Self::Html => "text/html",
+            Self::Xml => "application/xml",
+            Self::Text => "text/plain",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Odt => "odt",
+            Self::Ods => "ods",
+            Self::Odp => "odp",
+            Self::Odg => "odg",
+            Self::Docx => "docx",
+            Self::Xlsx => "xlsx",
+            Self::Pptx => "pptx",
+            Self::Html => "html",
+            Self::Xml => "xml",
+            Self::Text => "txt",
+        }
+    }
+}
+
+fn invalid<T: Into<String>>(reason: T) -> CarboneError {
+    CarboneError::InvalidTemplate(reason.into())
+}
+
+fn corrupt(e: ZipError) -> CarboneError {
+    match e {
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => {
+            invalid("the template is password-protected")
+        }
+        e => invalid(format!("the template is corrupt: {}", e)),
+    }
+}
+
+fn sniff_zip(content: &[u8]) -> Result<TemplateFormat> {
+    let mut archive = ZipArchive::new(Cursor::new(content)).map_err(corrupt)?;
+
+    let too_large = || {
+        invalid(format!(
+            "the template decompresses to more than the {} bytes allowed",
+            MAX_DECOMPRESSED_SIZE
+        ))
+    };
+
+    let mut total: u64 = 0;
+
+    // read every entry, checking the CRCs and the encryption flags
+    for index in 0..archive.len() {
+        let entry = archive.by_index(index).map_err(corrupt)?;
+        let size = entry.size();
+
+        total = total
+            .checked_add(size)
+            .filter(|&total| total <= MAX_DECOMPRESSED_SIZE)
+            .ok_or_else(too_large)?;
+
+        if size >= MIN_SUSPICIOUS_SIZE
+            && size / entry.compressed_size().max(1) > MAX_COMPRESSION_RATIO
+        {
+            return Err(invalid(format!(
+                "the entry {:?} of the template is compressed more than {} times",
+                entry.name(),
+                MAX_COMPRESSION_RATIO
+            )));
+        }
+
+        // the declared size is not trusted, an entry is never decompressed further
+        let read = io::copy(&mut entry.take(size + 1), &mut io::sink())
+            .map_err(|e| invalid(format!("the template is corrupt: {}", e)))?;
+
+        if read > size {
+            return Err(invalid(
+                "the template is corrupt: an entry is larger than declared",
+            ));
+        }
+    }
+
+    if let Some(mimetype) = read_entry(&mut archive, "mimetype")? {
+        let format = match mimetype.trim() {
+            m if m.starts_with("application/vnd.oasis.opendocument.text") => TemplateFormat::Odt,
+            m if m.starts_with("application/vnd.oasis.opendocument.spreadsheet") => {
+                TemplateFormat::Ods
+            }
+            m if m.starts_with("application/vnd.oasis.opendocument.presentation") => {
+                TemplateFormat::Odp
+            }
+            m if m.starts_with("application/vnd.oasis.opendocument.graphics") => {
+                TemplateFormat::Odg
+            }
+            m => return Err(invalid(format!("unsupported OpenDocument type {:?}", m))),
+        };
+
+        // ODF encrypts the entries itself, declaring it in the manifest
+        if let Some(manifest) = read_entry(&mut archive, "META-INF/manifest.xml")? {
+            if manifest.contains("encryption-data") {
+                return Err(invalid("the template is password-protected"));
+            }
+        }
+
+        return Ok(format);
+    }
+
+    if archive.by_name("[Content_Types].xml").is_ok() {
+        let has_dir = |dir: &str| archive.file_names().any(|name| name.starts_with(dir));
+
+        return if has_dir("word/") {
+            Ok(TemplateFormat::Docx)
+        } else if has_dir("xl/") {
+            Ok(TemplateFormat::Xlsx)
+        } else if has_dir("ppt/") {
+            Ok(TemplateFormat::Pptx)
+        } else {
+            Err(invalid("unsupported Office Open XML document"))
+        };
+    }
+
+    Err(invalid(
+        "the archive is neither an OpenDocument nor an Office Open XML document",
+    ))
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Option<String>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(_) => return Ok(None),
+    };
+
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| invalid(format!("the template is corrupt: {}", e)))?;
+
+    Ok(Some(content))
+}
+
+fn sniff_text(content: &[u8]) -> Result<TemplateFormat> {
+    let content = content.strip_prefix(UTF8_BOM).unwrap_or(content);
+
+    let text = match std::str::from_utf8(content) {
+        Ok(text) if !text.contains('\0') => text,
+        _ => return Err(invalid("unsupported binary template")),
+    };
+
+    let start = text.trim_start().as_bytes();
+    let head = start[..start.len().min(1024)].to_ascii_lowercase();
+
+    if head.starts_with(b"<!doctype html") || head.starts_with(b"<html") {
+        return Ok(TemplateFormat::Html);
+    }
+
+    if head.starts_with(b"<") {
+        // XHTML is sent as HTML
+        if head.windows(5).any(|w| w == b"<html") {
+            return Ok(TemplateFormat::Html);
+        }
+        return Ok(TemplateFormat::Xml);
+    }
+
+    Ok(TemplateFormat::Text)
+}
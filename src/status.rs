@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::errors::CarboneError;
+use crate::types::{ApiVersion, Result};
+
+/// The status of the Carbone service, as returned by `GET /status`.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ServiceStatus {
+    pub success: bool,
+    #[serde(default)]
+    pub code: Option<u16>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Version of the Carbone server, e.g. "4.22.8".
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Why the server may not support the configured api_version,
+    /// set by `negotiate_version` with `VersionCheck::Warn`.
+    #[serde(skip)]
+    pub warning: Option<String>,
+}
+
+impl ServiceStatus {
+    pub fn is_healthy(&self) -> bool {
+        self.success
+    }
+
+    /// The major version of the server, i.e. the latest API version it supports.
+    pub fn api_version(&self) -> Option<u32> {
+        self.version
+            .as_deref()
+            .and_then(|version| version.trim().trim_start_matches('v').split('.').next())
+            .and_then(|major| major.parse().ok())
+    }
+
+    /// Check the server supports the configured api_version, unknown when it sends no version.
+    pub fn supports(&self, api_version: &ApiVersion) -> Option<bool> {
+        let requested = api_version.as_str().parse::<u32>().ok()?;
+        self.api_version().map(|server| requested <= server)
+    }
+}
+
+/// What to do when the server does not support the configured api_version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCheck {
+    /// Go on, setting the `warning` of the returned status.
+    Warn,
+    /// Fail with `CarboneError::UnsupportedApiVersion`.
+    Fail,
+}
+
+impl VersionCheck {
+    pub(crate) fn check(&self, status: &mut ServiceStatus, api_version: &ApiVersion) -> Result<()> {
+        if status.supports(api_version) != Some(false) {
+            return Ok(());
+        }
+
+        let error = CarboneError::UnsupportedApiVersion {
+            requested: api_version.as_str().to_string(),
+            server: status.version.clone().unwrap_or_default(),
+        };
+
+        match self {
+            Self::Warn => {
+                status.warning = Some(error.to_string());
+                Ok(())
+            }
+            Self::Fail => Err(error),
+        }
+    }
+}
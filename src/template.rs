@@ -1,4 +1,5 @@
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 use std::str;
 
@@ -9,17 +10,24 @@ use std::ops::Deref;
 use sha2::{Digest, Sha256};
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::archive::{canonical_hash, unzip_to_dir, zip_dir};
+use crate::diff::TemplateDiff;
 use crate::errors::CarboneError;
+use crate::marker::{parse_markers, Marker, MarkerTree};
+use crate::sniff::TemplateFormat;
 use crate::types::*;
 
 use crate::types::Result;
 
+/// A template, read from a file on disk or held in memory.
 #[derive(Debug, Clone)]
 pub struct TemplateFile {
     path: String,
     pub content: Option<Vec<u8>>,
-    pub metadata: Metadata,
+    /// The metadata of the file, `None` for a template held in memory.
+    pub metadata: Option<Metadata>,
 }
 
 impl TemplateFile {
@@ -37,25 +45,230 @@ impl TemplateFile {
         Ok(Self {
             path,
             content,
-            metadata,
+            metadata: Some(metadata),
         })
     }
 
+    /// Create a template held in memory, e.g. loaded from a database or with `include_bytes!`.
+    ///
+    /// The name is only used for its extension and as the file name of the upload.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rs::template::TemplateFile;
+    /// use carbone_sdk_rs::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let template_file = TemplateFile::from_bytes("invoice.odt", include_bytes!("../tests/data/template.odt"))?;
+    ///
+    ///     assert_eq!(template_file.name(), "invoice.odt");
+    ///     assert_eq!(template_file.extension(), Some("odt"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_bytes<N: Into<String>, B: Into<Vec<u8>>>(name: N, bytes: B) -> Result<Self> {
+        let name = name.into();
+
+        if name.is_empty() {
+            return Err(CarboneError::EmptyString("name".to_string()));
+        }
+
+        Ok(Self {
+            path: name,
+            content: Some(bytes.into()),
+            metadata: None,
+        })
+    }
+
+    /// Create a template held in memory from the whole content of a reader.
+    pub fn from_reader<N: Into<String>, R: Read>(name: N, mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Self::from_bytes(name, bytes)
+    }
+
     pub fn generate_id(&self, payload: Option<&str>) -> Result<TemplateId> {
-        let file_content = match self.content.to_owned() {
-            Some(c) => c,
-            None => fs::read(self.path_as_str())?,
-        };
+        let file_content = self.read_content()?;
 
         TemplateId::from_bytes(file_content, payload)
     }
 
+    /// Generate the template id with the given mode, see [`TemplateIdMode`].
+    pub fn generate_id_with_mode(
+        &self,
+        payload: Option<&str>,
+        mode: TemplateIdMode,
+    ) -> Result<TemplateId> {
+        TemplateId::from_bytes_with_mode(self.read_content()?, payload, mode)
+    }
+
+    /// Zip an unpacked ODF or OOXML template, e.g. kept in git as XML files, into a template
+    /// held in memory. The same files always give the same content, hence the same template id.
+    ///
+    /// The name of the template is the name of the directory, with the extension of its format.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rs::template::TemplateFile;
+    /// use carbone_sdk_rs::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let template_file = TemplateFile::from_unpacked_dir("templates/invoice")?;
+    ///
+    ///     assert_eq!(template_file.name(), "invoice.odt");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_unpacked_dir<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+
+        if !dir.is_dir() {
+            return Err(CarboneError::TemplateFileNotFound(
+                dir.display().to_string(),
+            ));
+        }
+
+        let content = zip_dir(dir)?;
+        let format = TemplateFormat::sniff(&content)?;
+
+        let dir_name = dir
+            .canonicalize()?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "template".to_string());
+
+        let name = match Path::new(&dir_name).extension() {
+            Some(extension) if extension == format.extension() => dir_name,
+            _ => format!("{}.{}", dir_name, format.extension()),
+        };
+
+        Self::from_bytes(name, content)
+    }
+
+    /// Extract a zipped template into a directory, the reverse of [`Self::from_unpacked_dir`].
+    pub fn unpack_to_dir<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        unzip_to_dir(&self.read_content()?, dir.as_ref())
+    }
+
+    /// Return the given content or, if none was given, the content of the file.
+    pub fn read_content(&self) -> Result<Vec<u8>> {
+        match self.content.to_owned() {
+            Some(c) => Ok(c),
+            None => Ok(fs::read(self.path_as_str())?),
+        }
+    }
+
+    /// Return the `{d.*}` and `{c.*}` markers used in the template.
+    pub fn markers(&self) -> Result<Vec<Marker>> {
+        parse_markers(&self.read_content()?)
+    }
+
+    pub fn marker_tree(&self) -> Result<MarkerTree> {
+        Ok(MarkerTree::from_markers(&self.markers()?))
+    }
+
+    /// Derive a JSON Schema (draft 2020-12) of the render body expected by the template.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rs::template::TemplateFile;
+    /// use carbone_sdk_rs::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+    ///     let schema = template_file.json_schema()?;
+    ///
+    ///     assert_eq!(schema["properties"]["data"]["type"], "object");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn json_schema(&self) -> Result<Value> {
+        Ok(self.marker_tree()?.json_schema())
+    }
+
+    /// Detect the format of the template from its content, see [`TemplateFormat::sniff`].
+    pub fn format(&self) -> Result<TemplateFormat> {
+        TemplateFormat::sniff(&self.read_content()?)
+    }
+
+    /// The path of the file, or the name of a template held in memory.
     pub fn path_as_str(&self) -> &str {
         &self.path
     }
+
+    /// The file name, without the directories of the path.
+    pub fn name(&self) -> &str {
+        Path::new(&self.path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.path)
+    }
+
+    pub fn extension(&self) -> Option<&str> {
+        Path::new(&self.path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+    }
+
+    /// Whether the template is held in memory rather than read from a file.
+    pub fn is_in_memory(&self) -> bool {
+        self.metadata.is_none()
+    }
+}
+
+/// Compare the markers of two versions of a template: the markers added, removed
+/// or given other formatters, and the loops and conditions which changed.
+///
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rs::template::{self, TemplateFile};
+/// use carbone_sdk_rs::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let old = TemplateFile::new("invoice-v1.odt".to_string(), None)?;
+///     let new = TemplateFile::new("invoice-v2.odt".to_string(), None)?;
+///
+///     let diff = template::diff(&old, &new)?;
+///
+///     print!("{}", diff);
+///
+///     Ok(())
+/// }
+/// ```
+pub fn diff(old: &TemplateFile, new: &TemplateFile) -> Result<TemplateDiff> {
+    Ok(TemplateDiff::from_markers(&old.markers()?, &new.markers()?))
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// How a template id is computed from the content of a template.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemplateIdMode {
+    /// Hash the bytes of the file, as the Carbone API does.
+    #[default]
+    Raw,
+    /// Hash the sorted entries of a zipped template, decompressed and normalized, so that
+    /// re-saving a template without changes gives the same id. Only meant as a local id
+    /// or cache key: it differs from the id the Carbone API gives to the template.
+    /// Flat templates (HTML, XML, text) are hashed as in the `Raw` mode.
+    Canonical,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct TemplateId(Id);
 
 impl TemplateId {
@@ -99,6 +312,44 @@ impl TemplateId {
         Self::new(result.to_lowercase())
 
     }
+
+    /// Compute the template id of a template with the given mode.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::fs;
+    ///
+    /// use carbone_sdk_rs::template::{TemplateId, TemplateIdMode};
+    /// use carbone_sdk_rs::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let content = fs::read("tests/data/template.odt")?;
+    ///     let template_id = TemplateId::from_bytes_with_mode(content, None, TemplateIdMode::Canonical)?;
+    ///
+    ///     assert_eq!(template_id.as_str().len(), 64);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_bytes_with_mode(
+        data: Vec<u8>,
+        payload: Option<&str>,
+        mode: TemplateIdMode,
+    ) -> Result<Self> {
+        if mode == TemplateIdMode::Raw || !data.starts_with(b"PK\x03\x04") {
+            return Self::from_bytes(data, payload);
+        }
+
+        let mut sha256 = Sha256::new();
+
+        sha256.update(payload.unwrap_or(""));
+        canonical_hash(&data, &mut sha256)?;
+
+        Self::new(format!("{:x}", sha256.finalize()))
+    }
 }
 
 impl Deref for TemplateId {
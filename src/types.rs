@@ -1,23 +1,150 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use data_encoding::BASE64URL_NOPAD;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
 
-use crate::errors::CarboneError;
+use crate::errors::{CarboneError, ConfigError};
 
 pub type Result<T> = std::result::Result<T, CarboneError>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ApiJsonToken(String);
+const REDACTED: &str = "[REDACTED]";
+
+/// The API token, a JWT whose signature is verified by the Carbone API only.
+///
+/// The value is redacted from `Debug`/`Display` and zeroized on drop.
+#[derive(Clone, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct ApiJsonToken {
+    value: String,
+    header: JwtHeader,
+    claims: JwtClaims,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct JwtHeader {
+    pub alg: String,
+    #[serde(default)]
+    pub typ: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct JwtClaims {
+    #[serde(default)]
+    pub iss: Option<String>,
+    /// Expiration time, in seconds since the unix epoch.
+    #[serde(default)]
+    pub exp: Option<u64>,
+    /// Issued at, in seconds since the unix epoch.
+    #[serde(default)]
+    pub iat: Option<u64>,
+}
 
 impl ApiJsonToken {
-    pub fn new(s: String) -> Result<Self> {
-        if s.len() >= 300 {
-            Ok(ApiJsonToken(s))
-        } else {
-            Err(CarboneError::Error("wrong token length".to_string()))
+    /// Create a new api_token from a JWT.
+    ///
+    /// The header and the claims are decoded but the signature is not verified.
+    /// An expired token is rejected.
+    pub fn new(mut s: String) -> Result<Self> {
+        // trimmed in place, e.g. the newline of a token read from a file
+        s.truncate(s.trim_end().len());
+        s.drain(..s.len() - s.trim_start().len());
+
+        let parts: Vec<&str> = s.split('.').collect();
+
+        if parts.len() != 3 || parts[2].is_empty() {
+            return Err(CarboneError::InvalidToken(
+                "a JWT must have a header, a payload and a signature".to_string(),
+            ));
         }
+
+        let header: JwtHeader = decode_jwt_part(parts[0], "header")?;
+        let claims: JwtClaims = decode_jwt_part(parts[1], "payload")?;
+
+        let api_token = Self {
+            value: s,
+            header,
+            claims,
+        };
+
+        if let (true, Some(exp)) = (api_token.is_expired(), api_token.claims.exp) {
+            return Err(CarboneError::ExpiredToken(exp));
+        }
+
+        Ok(api_token)
     }
 
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.value
+    }
+
+    pub fn header(&self) -> &JwtHeader {
+        &self.header
+    }
+
+    pub fn claims(&self) -> &JwtClaims {
+        &self.claims
+    }
+
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.claims
+            .exp
+            .map(|exp| UNIX_EPOCH + Duration::from_secs(exp))
+    }
+
+    pub fn issued_at(&self) -> Option<SystemTime> {
+        self.claims
+            .iat
+            .map(|iat| UNIX_EPOCH + Duration::from_secs(iat))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at() {
+            Some(expires_at) => expires_at <= SystemTime::now(),
+            None => false,
+        }
+    }
+}
+
+fn decode_jwt_part<T: DeserializeOwned>(part: &str, name: &str) -> Result<T> {
+    let invalid = |e: String| CarboneError::InvalidToken(format!("{}: {}", name, e));
+
+    let bytes = BASE64URL_NOPAD
+        .decode(part.trim_end_matches('=').as_bytes())
+        .map_err(|e| invalid(e.to_string()))?;
+
+    serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))
+}
+
+impl fmt::Debug for ApiJsonToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiJsonToken")
+            .field("value", &REDACTED)
+            .field("header", &self.header)
+            .field("claims", &self.claims)
+            .finish()
+    }
+}
+
+impl fmt::Display for ApiJsonToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Drop for ApiJsonToken {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl TryFrom<String> for ApiJsonToken {
+    type Error = CarboneError;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::new(s)
     }
 }
 
@@ -25,11 +152,17 @@ impl ApiJsonToken {
 pub struct ApiVersion(String);
 
 impl ApiVersion {
+    /// Create a new api_version, the major version of the Carbone API, e.g. "4".
     pub fn new(s: String) -> Result<Self> {
-        if !s.is_empty() {
-            Ok(ApiVersion(s))
-        } else {
-            Err(CarboneError::Error("wrong token length".to_string()))
+        let api_version = ApiVersion(s);
+        api_version.validate()?;
+        Ok(api_version)
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), ConfigError> {
+        match self.0.parse::<u32>() {
+            Ok(v) if v > 0 => Ok(()),
+            _ => Err(ConfigError::UnsupportedApiVersion(self.0.to_string())),
         }
     }
 
@@ -38,7 +171,7 @@ impl ApiVersion {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct Id(String);
 
 impl Id {
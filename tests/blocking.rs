@@ -48,12 +48,12 @@ mod tests {
 
         let api_token = &helper.create_api_token()?;
 
-        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone = Carbone::new(&config, api_token)?;
         let is_deleted = carbone.delete_template(template_id)?;
 
         mock_server.assert();
 
-        assert_eq!(is_deleted, true);
+        assert!(is_deleted);
 
         Ok(())
     }
@@ -556,6 +556,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_upload_template_from_bytes() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"invoice.odt\"");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": "foo" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let template_file =
+            TemplateFile::from_bytes("invoice.odt", fs::read("tests/data/template.odt")?)?;
+
+        let carbone = Carbone::with_auth(&config, carbone_sdk_rs::auth::Auth::None)?;
+        let template_id = carbone.upload_template(&template_file, None)?;
+
+        mock_server.assert();
+        assert_eq!(template_id.as_str(), "foo");
+
+        Ok(())
+    }
+
     #[test]
     fn test_upload_template_with_payload() -> Result<(), CarboneError> {
         let template_id_expected = TemplateId::new(
@@ -632,7 +661,7 @@ mod tests {
 
         let template_file = TemplateFile::new("tests/data/template.test.txt".to_string(), None)?;
 
-        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone = Carbone::new(&config, api_token)?;
         let result = carbone.upload_template(&template_file, None);
 
         let expected_error = CarboneError::Error(error_msg.to_string());
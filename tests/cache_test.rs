@@ -0,0 +1,225 @@
+use std::fs;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use bytes::Bytes;
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::auth::Auth;
+use carbone_sdk_rs::cache::*;
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::template::TemplateId;
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::{Helper, TempDir};
+
+fn key(template_id: &str, body: &str) -> CacheKey {
+    let json_data = JsonData::new(body.to_string()).unwrap();
+    CacheKey::new(TemplateId::new(template_id).unwrap(), &json_data).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            key(
+                "tpl",
+                r#"{ "data": { "a": 1, "b": [1, 2] }, "convertTo": "pdf" }"#
+            ),
+            key("tpl", r#"{"convertTo":"pdf","data":{"b":[1,2],"a":1}}"#)
+        );
+        assert_ne!(
+            key("tpl", r#"{ "data": { "b": [1, 2] } }"#),
+            key("tpl", r#"{ "data": { "b": [2, 1] } }"#)
+        );
+        assert_ne!(
+            key("tpl", r#"{ "data": {} }"#).digest(),
+            key("other", r#"{ "data": {} }"#).digest()
+        );
+
+        let json_data = JsonData::new("not json".to_string()).unwrap();
+        assert!(matches!(
+            CacheKey::new(TemplateId::new("tpl").unwrap(), &json_data),
+            Err(CarboneError::RequestBodyNotWellFormedJsonError)
+        ));
+    }
+
+    #[test]
+    fn test_memory_report_cache() {
+        let cache = MemoryReportCache::new(10);
+
+        let (a, b, c) = (key("a", "{}"), key("b", "{}"), key("c", "{}"));
+
+        cache.put(&a, &Bytes::from_static(b"aaaa"));
+        cache.put(&b, &Bytes::from_static(b"bbbb"));
+
+        // a is now the most recently used
+        assert_eq!(cache.get(&a), Some(Bytes::from_static(b"aaaa")));
+
+        cache.put(&c, &Bytes::from_static(b"cccc"));
+
+        assert_eq!(cache.get(&b), None);
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&c).is_some());
+        assert_eq!((cache.len(), cache.size()), (2, 8));
+
+        // replaced, not added
+        cache.put(&c, &Bytes::from_static(b"cc"));
+        assert_eq!((cache.len(), cache.size()), (2, 6));
+
+        // too large to be cached
+        cache.put(&b, &Bytes::from_static(b"bbbbbbbbbbbb"));
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(cache.len(), 2);
+
+        let cache = MemoryReportCache::new(10).ttl(Duration::from_millis(50));
+        cache.put(&a, &Bytes::from_static(b"aaaa"));
+
+        assert!(cache.get(&a).is_some());
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(cache.get(&a), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_disk_report_cache() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("disk_report_cache");
+        let dir = temp_dir.path();
+        let cache = DiskReportCache::new(dir, 10)?;
+
+        let (a, b, c) = (key("a", "{}"), key("b", "{}"), key("c", "{}"));
+
+        cache.put(&a, &Bytes::from_static(b"aaaa"));
+        thread::sleep(Duration::from_millis(20));
+        cache.put(&b, &Bytes::from_static(b"bbbb"));
+        thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(cache.get(&a), Some(Bytes::from_static(b"aaaa")));
+
+        // the oldest report is removed first
+        cache.put(&c, &Bytes::from_static(b"cccc"));
+
+        assert_eq!(cache.get(&a), None);
+        assert!(cache.get(&b).is_some());
+        assert!(cache.get(&c).is_some());
+
+        // shared with another cache of the same directory
+        let other = DiskReportCache::new(dir, 10)?.ttl(Duration::from_millis(50));
+
+        assert!(other.get(&c).is_some());
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(other.get(&c), None);
+        assert_eq!(fs::read_dir(dir)?.count(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_report_with_cache() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let render_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/tpl");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "foo.pdf" }
+            }));
+        });
+
+        let report_mock = server.mock(|when, then| {
+            when.method("GET").path("/render/foo.pdf");
+            then.status(200).body("%PDF");
+        });
+
+        let cache = Arc::new(MemoryReportCache::new(1024));
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::bearer(api_token))
+            .report_cache(cache.clone())
+            .build()?;
+
+        let template_id = TemplateId::new("tpl")?;
+
+        for body in [
+            r#"{ "data": { "a": 1, "b": 2 } }"#,
+            r#"{"data":{"b":2,"a":1}}"#,
+        ] {
+            let json_data = JsonData::new(body.to_string())?;
+            let report_content = carbone
+                .generate_report_with_template_id(template_id.clone(), json_data)
+                .await?;
+
+            assert_eq!(report_content, Bytes::from_static(b"%PDF"));
+        }
+
+        render_mock.assert_hits(1);
+        report_mock.assert_hits(1);
+        assert_eq!(cache.len(), 1);
+
+        // sent as without a cache, the key of the render cannot be computed
+        let json_data = JsonData::new("not json".to_string())?;
+        let report_content = carbone
+            .generate_report_with_template_id(template_id, json_data)
+            .await?;
+
+        assert_eq!(report_content, Bytes::from_static(b"%PDF"));
+        render_mock.assert_hits(2);
+        assert_eq!(cache.len(), 1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_generate_report_with_cache() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+        let temp_dir = TempDir::new("blocking_report_cache");
+        let dir = temp_dir.path();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let render_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/tpl");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "foo.pdf" }
+            }));
+        });
+
+        server.mock(|when, then| {
+            when.method("GET").path("/render/foo.pdf");
+            then.status(200).body("%PDF");
+        });
+
+        let carbone = carbone_sdk_rs::blocking::Carbone::builder(&config)
+            .report_cache(Arc::new(DiskReportCache::new(dir, 1024)?))
+            .build()?;
+
+        let template_id = TemplateId::new("tpl")?;
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+
+        for _ in 0..2 {
+            let report_content =
+                carbone.generate_report_with_template_id(template_id.clone(), json_data.clone())?;
+
+            assert_eq!(report_content, Bytes::from_static(b"%PDF"));
+        }
+
+        render_mock.assert_hits(1);
+
+        Ok(())
+    }
+}
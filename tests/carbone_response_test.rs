@@ -5,7 +5,6 @@ mod tests {
     use carbone_sdk_rs::errors::CarboneError;
     use carbone_sdk_rs::render::RenderId;
     use carbone_sdk_rs::template::TemplateId;
-    use serde_json;
 
     #[test]
     fn test_deserialize_response_succeed() -> Result<(), CarboneError> {
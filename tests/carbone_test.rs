@@ -50,12 +50,12 @@ mod tests {
 
         let api_token = &helper.create_api_token()?;
 
-        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone = Carbone::new(&config, api_token)?;
         let is_deleted = carbone.delete_template(template_id).await.unwrap();
 
         mock_server.assert();
 
-        assert_eq!(is_deleted, true);
+        assert!(is_deleted);
 
         Ok(())
     }
@@ -295,7 +295,7 @@ mod tests {
 
         let template_name = "template.odt".to_string();
         let template_path = format!("tests/data/{}", template_name);
-        let template_data = fs::read(template_path.to_owned())?;
+        let template_data = fs::read(&template_path)?;
 
         let template_file = TemplateFile::new(template_path, Some(template_data.to_owned()))?;
         let template_id = template_file.generate_id(None)?;
@@ -657,7 +657,7 @@ mod tests {
         let file_path = format!("tests/data/{}", file_name);
         let filte_content = fs::read(file_path)?;
 
-        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone = Carbone::new(&config, api_token)?;
         let result = carbone
             .upload_template(file_name, filte_content, None)
             .await;
@@ -0,0 +1,204 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::circuit_breaker::{CircuitBreakerConfig, CircuitState};
+use carbone_sdk_rs::config::Config;
+use carbone_sdk_rs::errors::{CarboneError, ConfigError};
+use carbone_sdk_rs::template::TemplateId;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_config_from_str() -> Result<(), CarboneError> {
+        let config = Config::from_str(
+            r#"{
+            "apiUrl": "http://127.0.0.1",
+            "apiTimeout": 4,
+            "apiVersion" : "4",
+            "circuitBreaker": { "failureThreshold": 2 }
+        }"#,
+        )?;
+
+        let expected = CircuitBreakerConfig {
+            failure_threshold: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(config.circuit_breaker, Some(expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_zero_failure_threshold() {
+        let result = Config::from_str(
+            r#"{
+            "apiUrl": "http://127.0.0.1",
+            "apiTimeout": 4,
+            "apiVersion" : "4",
+            "circuitBreaker": { "failureThreshold": 0 }
+        }"#,
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            ConfigError::InvalidValue(
+                "circuitBreaker.failureThreshold".to_string(),
+                "0".to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_and_fails_fast() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+
+        let mut config = helper.create_config_for_mock_server(None)?;
+        // nothing listens on this port, the connection is refused
+        config.api_url = "http://127.0.0.1:1".to_string();
+        config.circuit_breaker = Some(CircuitBreakerConfig {
+            failure_threshold: 2,
+            ..Default::default()
+        });
+
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        for _ in 0..2 {
+            let result = carbone.delete_template(TemplateId::new("foo")?).await;
+            assert!(matches!(result, Err(CarboneError::RequestError(_))));
+        }
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+
+        assert!(matches!(result, Err(CarboneError::CircuitOpen)));
+        assert_eq!(carbone.circuit_state(), CircuitState::Open);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe_closes_the_circuit() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let mut config = helper.create_config_for_mock_server(Some(&server))?;
+        config.circuit_breaker = Some(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_duration: 1,
+            half_open_probes: 1,
+        });
+
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let mut mock_server_error = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(500)
+                .json_body(json!({ "success": false, "error": "down" }));
+        });
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+        assert!(result.is_err());
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+        assert!(matches!(result, Err(CarboneError::CircuitOpen)));
+
+        mock_server_error.assert_hits(1);
+        mock_server_error.delete();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        assert_eq!(carbone.circuit_state(), CircuitState::HalfOpen);
+
+        let is_deleted = carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        mock_server.assert();
+
+        assert!(is_deleted);
+        assert_eq!(carbone.circuit_state(), CircuitState::Closed);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_probe_is_given_back() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let mut config = helper.create_config_for_mock_server(Some(&server))?;
+        config.circuit_breaker = Some(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_duration: 1,
+            half_open_probes: 1,
+        });
+
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let mut mock_server_error = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(500)
+                .json_body(json!({ "success": false, "error": "down" }));
+        });
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+        assert!(result.is_err());
+
+        mock_server_error.delete();
+
+        let mut mock_slow = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200)
+                .delay(Duration::from_secs(5))
+                .json_body(json!({ "success": true }));
+        });
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        // the probe is dropped before its response
+        let result = tokio::time::timeout(
+            Duration::from_millis(200),
+            carbone.delete_template(TemplateId::new("foo")?),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(carbone.circuit_state(), CircuitState::HalfOpen);
+
+        mock_slow.delete();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let is_deleted = carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        mock_server.assert();
+
+        assert!(is_deleted);
+        assert_eq!(carbone.circuit_state(), CircuitState::Closed);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use carbone_sdk_rs::diff::*;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::template::{self, TemplateFile};
+
+fn html(body: &str) -> Result<TemplateFile, CarboneError> {
+    TemplateFile::from_bytes(
+        "template.html",
+        format!("<html><body>{}</body></html>", body),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_diff_identical_templates() -> Result<(), CarboneError> {
+        let old = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+        let new = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+
+        let diff = template::diff(&old, &new)?;
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_markers() -> Result<(), CarboneError> {
+        let old = html("{d.name} {d.price:formatN(2)} {d.tax}")?;
+        let new = html("{d.name} {d.price:formatN(3)} {d.total}")?;
+
+        let diff = template::diff(&old, &new)?;
+
+        let changes: Vec<(ChangeKind, ChangeSubject, &str)> = diff
+            .changes
+            .iter()
+            .map(|change| (change.kind, change.subject, change.path.as_str()))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (ChangeKind::Modified, ChangeSubject::Marker, "d.price"),
+                (ChangeKind::Removed, ChangeSubject::Marker, "d.tax"),
+                (ChangeKind::Added, ChangeSubject::Marker, "d.total"),
+            ]
+        );
+
+        let price = &diff.changes[0];
+
+        assert_eq!(price.before, vec!["formatN(2)"]);
+        assert_eq!(price.after, vec!["formatN(3)"]);
+        assert_eq!(price.locations.len(), 1);
+        assert_eq!(price.locations[0].offset, 21);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_loops_and_conditions() -> Result<(), CarboneError> {
+        let old = html(concat!(
+            "{d.lines[i].qty} {d.lines[i+1].qty}",
+            "{d.status:ifEQ(paid):show('Paid')}"
+        ))?;
+        let new = html(concat!(
+            "{d.items[i].qty} {d.items[i+1].qty}",
+            "{d.status:ifEQ(due):show('Due')} {d.note:ifEM():hideBegin()}"
+        ))?;
+
+        let diff = template::diff(&old, &new)?;
+
+        let changes: Vec<(ChangeKind, ChangeSubject, &str)> = diff
+            .changes
+            .iter()
+            .filter(|change| change.subject != ChangeSubject::Marker)
+            .map(|change| (change.kind, change.subject, change.path.as_str()))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (ChangeKind::Added, ChangeSubject::Loop, "d.items[i]"),
+                (ChangeKind::Removed, ChangeSubject::Loop, "d.lines[i]"),
+                (ChangeKind::Added, ChangeSubject::Condition, "d.note"),
+                (ChangeKind::Modified, ChangeSubject::Condition, "d.status"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_text_and_json() -> Result<(), CarboneError> {
+        let old = html("{d.price:formatN(2)} {d.tax}")?;
+        let new = html("{d.price} {d.lines[i].qty}")?;
+
+        let diff = template::diff(&old, &new)?;
+
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "+ loop d.lines[i] (offset 22)\n",
+                "+ marker d.lines[i].qty (offset 22)\n",
+                "~ marker d.price: formatN(2) -> no formatter (offset 12)\n",
+                "- marker d.tax (offset 33)\n",
+            )
+        );
+
+        let json = serde_json::to_value(&diff).unwrap();
+
+        assert_eq!(json["changes"][0]["kind"], "added");
+        assert_eq!(json["changes"][0]["subject"], "loop");
+        assert_eq!(json["changes"][2]["before"][0], "formatN(2)");
+
+        Ok(())
+    }
+}
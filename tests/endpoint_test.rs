@@ -0,0 +1,319 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::auth::Auth;
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::endpoint::{Endpoint, EndpointPool};
+use carbone_sdk_rs::errors::{CarboneError, ConfigError};
+use carbone_sdk_rs::options::CallOptions;
+use carbone_sdk_rs::template::TemplateId;
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::Helper;
+
+// nothing listens on this port, the connection is refused
+const DOWN_URL: &str = "http://127.0.0.1:1";
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn endpoint(server: &MockServer, weight: u32) -> Result<Endpoint, CarboneError> {
+        Endpoint::new(format!("http://127.0.0.1:{}", server.port()), weight)
+    }
+
+    #[test]
+    fn test_empty_pool() {
+        let result = EndpointPool::new(vec![]);
+
+        assert!(matches!(
+            result.unwrap_err(),
+            CarboneError::ConfigError(ConfigError::MissingField(_))
+        ));
+    }
+
+    #[test]
+    fn test_endpoint_invalid_url() {
+        let result = Endpoint::new("carbone-1:4000", 1);
+
+        assert!(matches!(
+            result.unwrap_err(),
+            CarboneError::ConfigError(ConfigError::InvalidUrl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_weighted_selection() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+
+        let config = helper.create_config_for_mock_server(None)?;
+        let api_token = helper.create_api_token()?;
+
+        let server_a = MockServer::start();
+        let server_b = MockServer::start();
+
+        let body = json!({ "success": true });
+
+        let mock_a = server_a.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(body.clone());
+        });
+
+        let mock_b = server_b.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(body.clone());
+        });
+
+        let endpoints = EndpointPool::new(vec![endpoint(&server_a, 2)?, endpoint(&server_b, 1)?])?;
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::bearer(api_token))
+            .endpoints(Arc::new(endpoints))
+            .build()?;
+
+        for _ in 0..6 {
+            carbone.delete_template(TemplateId::new("foo")?).await?;
+        }
+
+        mock_a.assert_hits(4);
+        mock_b.assert_hits(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failover_and_unhealthy_endpoint() -> Result<(), CarboneError> {
+        let config = Helper::new().create_config_for_mock_server(None)?;
+
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let endpoints =
+            EndpointPool::new(vec![Endpoint::new(DOWN_URL, 10)?, endpoint(&server, 1)?])?
+                .failure_threshold(2)
+                .probe_interval(Duration::from_secs(3600));
+
+        let endpoints = Arc::new(endpoints);
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::None)
+            .endpoints(endpoints.clone())
+            .build()?;
+
+        for _ in 0..4 {
+            carbone.delete_template(TemplateId::new("foo")?).await?;
+        }
+
+        mock_server.assert_hits(4);
+
+        assert!(!endpoints.is_healthy(DOWN_URL));
+        assert!(endpoints.is_healthy(&format!("http://127.0.0.1:{}", server.port())));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_probe_unhealthy_endpoint_once() -> Result<(), CarboneError> {
+        let config = Helper::new().create_config_for_mock_server(None)?;
+
+        let failing_server = MockServer::start();
+        let server = MockServer::start();
+
+        let mock_failing_server = failing_server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(503).json_body(json!({ "success": false }));
+        });
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let endpoints =
+            EndpointPool::new(vec![endpoint(&failing_server, 10)?, endpoint(&server, 1)?])?
+                .failure_threshold(1)
+                .probe_interval(Duration::from_millis(100));
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::None)
+            .endpoints(Arc::new(endpoints))
+            .build()?;
+
+        carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        // only one of the concurrent requests probes the unhealthy endpoint
+        let (a, b, c) = tokio::join!(
+            carbone.delete_template(TemplateId::new("foo")?),
+            carbone.delete_template(TemplateId::new("foo")?),
+            carbone.delete_template(TemplateId::new("foo")?),
+        );
+
+        assert!(a? && b? && c?);
+
+        mock_failing_server.assert_hits(2);
+        mock_server.assert_hits(4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timeout_failover_of_idempotent_requests_only() -> Result<(), CarboneError> {
+        let config = Helper::new().create_config_for_mock_server(None)?;
+
+        let slow_server = MockServer::start();
+        let server = MockServer::start();
+
+        let mock_slow_delete = slow_server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200)
+                .delay(Duration::from_secs(2))
+                .json_body(json!({ "success": true }));
+        });
+
+        let mock_slow_render = slow_server.mock(|when, then| {
+            when.method("POST");
+            then.status(200)
+                .delay(Duration::from_secs(2))
+                .json_body(json!({ "success": true, "data": { "renderId": "foo.pdf" } }));
+        });
+
+        let mock_delete = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST");
+            then.status(200)
+                .json_body(json!({ "success": true, "data": { "renderId": "foo.pdf" } }));
+        });
+
+        let endpoints = EndpointPool::new(vec![endpoint(&slow_server, 1)?, endpoint(&server, 0)?])?;
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::None)
+            .endpoints(Arc::new(endpoints))
+            .build()?;
+
+        let options = CallOptions::new().timeout(Duration::from_millis(100));
+
+        let is_deleted = carbone
+            .delete_template_with(TemplateId::new("foo")?, &options)
+            .await?;
+
+        assert!(is_deleted);
+        mock_slow_delete.assert();
+        mock_delete.assert();
+
+        // the render may have been processed, it is not sent again
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+        let result = carbone
+            .render_data_with(TemplateId::new("foo")?, json_data, &options)
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::RequestError(e)) if e.is_timeout()));
+        mock_slow_render.assert();
+        mock_render.assert_hits(0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_server_error_failover() -> Result<(), CarboneError> {
+        let config = Helper::new().create_config_for_mock_server(None)?;
+
+        let failing_server = MockServer::start();
+        let server = MockServer::start();
+
+        let mock_failing_server = failing_server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(503).json_body(json!({ "success": false }));
+        });
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let endpoints =
+            EndpointPool::new(vec![endpoint(&failing_server, 1)?, endpoint(&server, 0)?])?;
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::None)
+            .endpoints(Arc::new(endpoints))
+            .build()?;
+
+        let is_deleted = carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        mock_failing_server.assert();
+        mock_server.assert();
+
+        assert!(is_deleted);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_report_from_the_rendering_endpoint() -> Result<(), CarboneError> {
+        let config = Helper::new().create_config_for_mock_server(None)?;
+
+        let server_a = MockServer::start();
+        let server_b = MockServer::start();
+
+        let template_id = TemplateId::new("foo")?;
+        let render_id_value = "MTAuMjAuMjEuNDAgICAgBY4OM11wQg11ekv6_R0n0wcmVwb3J0.pdf";
+
+        let mock_render = server_a.mock(|when, then| {
+            when.method("POST").path("/render/foo");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": render_id_value }
+            }));
+        });
+
+        let mock_report_a = server_a.mock(|when, then| {
+            when.method("GET")
+                .path(format!("/render/{}", render_id_value));
+            then.status(200).body("report");
+        });
+
+        let mock_report_b = server_b.mock(|when, then| {
+            when.method("GET");
+            then.status(404)
+                .json_body(json!({ "success": false, "error": "not found" }));
+        });
+
+        let endpoints = EndpointPool::new(vec![endpoint(&server_a, 1)?, endpoint(&server_b, 1)?])?;
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::None)
+            .endpoints(Arc::new(endpoints))
+            .build()?;
+
+        let json_data = JsonData::new(r#"{ "data": {}, "convertTo": "pdf" }"#.to_string())?;
+
+        // the round-robin would send the next request to the server b
+        let render_id = carbone.render_data(template_id, json_data).await?;
+        let report_content = carbone.get_report(&render_id).await?;
+
+        mock_render.assert();
+        mock_report_a.assert();
+        mock_report_b.assert_hits(0);
+
+        assert_eq!(report_content, "report");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+use std::fs;
+use std::sync::Arc;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::auth::Auth;
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::i18n::*;
+use carbone_sdk_rs::options::CallOptions;
+use carbone_sdk_rs::template::{TemplateFile, TemplateId};
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::{parse_body, Helper, TempDir};
+
+fn catalog(lang: &str, translations: &[(&str, &str)]) -> Catalog {
+    let mut catalog = Catalog::new(lang);
+
+    for (text, translation) in translations {
+        catalog.messages.insert(
+            text.to_string(),
+            Message {
+                translation: Some(translation.to_string()),
+                references: Vec::new(),
+            },
+        );
+    }
+
+    catalog
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_extract_catalog() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::from_bytes(
+            "invoice.html",
+            "<p>{t(Invoice)} {d.id}</p><p>{t(Total (excl. VAT))}</p><p>{t(Invoice)}</p>",
+        )?;
+
+        let catalog = Catalog::extract(&template_file)?;
+
+        assert_eq!(catalog.lang, None);
+        assert_eq!(
+            catalog.messages.keys().collect::<Vec<_>>(),
+            vec!["Invoice", "Total (excl. VAT)"]
+        );
+        assert_eq!(
+            catalog.messages["Invoice"].references,
+            vec!["invoice.html:3", "invoice.html:58"]
+        );
+        assert_eq!(catalog.untranslated(), vec!["Invoice", "Total (excl. VAT)"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_po_round_trip() -> Result<(), CarboneError> {
+        let mut catalog = catalog("fr-fr", &[("Invoice", "Facture")]);
+        catalog.messages.insert(
+            "Say \"hello\"\n".to_string(),
+            Message {
+                translation: None,
+                references: vec!["content.xml:12".to_string()],
+            },
+        );
+
+        let po = catalog.to_po();
+
+        assert!(po.contains("\"Language: fr-fr\\n\"\n"));
+        assert!(po.contains("#: content.xml:12\nmsgid \"Say \\\"hello\\\"\\n\"\nmsgstr \"\"\n"));
+        assert_eq!(Catalog::from_po(&po)?, catalog);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_po() -> Result<(), CarboneError> {
+        let po = r#"
+# translator comment
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"Language: de\n"
+
+msgid "Invoice"
+msgstr "Rechnung"
+
+#, fuzzy
+msgid "Total"
+msgstr "Summe"
+
+msgid ""
+"Thank you "
+"for your order"
+msgstr ""
+"Vielen Dank für "
+"Ihre Bestellung"
+"#;
+
+        let catalog = Catalog::from_po(po)?;
+
+        assert_eq!(catalog.lang.as_deref(), Some("de"));
+        assert_eq!(
+            catalog.translations(),
+            json!({
+                "Invoice": "Rechnung",
+                "Thank you for your order": "Vielen Dank für Ihre Bestellung"
+            })
+            .as_object()
+            .unwrap()
+            .clone()
+        );
+        assert_eq!(catalog.untranslated(), vec!["Total"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_catalog_and_merge() -> Result<(), CarboneError> {
+        let previous = Catalog::from_json(r#"{ "Invoice": "Facture", "Total": "" }"#)?;
+
+        assert_eq!(
+            previous.to_json(),
+            json!({ "Invoice": "Facture", "Total": "" })
+        );
+
+        let template_file =
+            TemplateFile::from_bytes("invoice.html", "<p>{t(Invoice)} {t(Due date)}</p>")?;
+
+        let mut catalog = Catalog::extract(&template_file)?;
+        catalog.merge(&previous);
+
+        assert_eq!(
+            catalog.to_json(),
+            json!({ "Due date": "", "Invoice": "Facture" })
+        );
+
+        assert!(matches!(
+            Catalog::from_json(r#"{ "Invoice": 1 }"#),
+            Err(CarboneError::ParseError(_, _))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_translations() -> Result<(), CarboneError> {
+        let mut translations = Translations::new();
+        translations.insert(catalog("fr", &[("Invoice", "Facture")]))?;
+        translations.insert(catalog("de-DE", &[("Invoice", "Rechnung")]))?;
+
+        assert_eq!(translations.langs(), vec!["de-de", "fr"]);
+
+        let json_data = JsonData::new(
+            r#"{ "data": {}, "translations": { "fr-ca": { "Total": "Somme" } } }"#.to_string(),
+        )?;
+
+        // falls back to the primary language, keeping the translations of the body
+        let body = parse_body(&translations.apply(&json_data, Some("fr-ca"))?);
+
+        assert_eq!(body["lang"], "fr-ca");
+        assert_eq!(
+            body["translations"],
+            json!({ "fr-ca": { "Total": "Somme", "Invoice": "Facture" } })
+        );
+
+        // the lang of the body
+        let json_data = JsonData::new(r#"{ "data": {}, "lang": "de-de" }"#.to_string())?;
+        let body = parse_body(&translations.apply(&json_data, None)?);
+
+        assert_eq!(
+            body["translations"],
+            json!({ "de-de": { "Invoice": "Rechnung" } })
+        );
+
+        // no lang at all
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+        assert_eq!(translations.apply(&json_data, None)?, json_data);
+
+        // a lang without a catalog, e.g. the one of the template
+        let body = parse_body(&translations.apply(&json_data, Some("en"))?);
+
+        assert_eq!(body, json!({ "data": {}, "lang": "en" }));
+
+        assert!(matches!(
+            translations.clone().strict().apply(&json_data, Some("es")),
+            Err(CarboneError::TranslationsNotFound(lang)) if lang == "es"
+        ));
+
+        assert!(matches!(
+            translations.insert(Catalog::default()),
+            Err(CarboneError::EmptyString(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_translations_dir() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("translations");
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir)?;
+
+        fs::write(
+            dir.join("fr-fr.po"),
+            "msgid \"Invoice\"\nmsgstr \"Facture\"\n",
+        )?;
+        fs::write(dir.join("de.json"), r#"{ "Invoice": "Rechnung" }"#)?;
+        fs::write(dir.join("README.md"), "not a catalog")?;
+
+        let translations = Translations::load_dir(dir)?;
+
+        assert_eq!(translations.langs(), vec!["de", "fr-fr"]);
+        assert_eq!(
+            translations.get("FR-FR").unwrap().translations()["Invoice"],
+            "Facture"
+        );
+
+        assert!(matches!(
+            Translations::load_dir(dir.join("missing")),
+            Err(CarboneError::FileNotFound(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_with_translations() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mut translations = Translations::new();
+        translations.insert(catalog("fr", &[("Invoice", "Facture")]))?;
+
+        let mut other_translations = Translations::new();
+        other_translations.insert(catalog("fr", &[("Invoice", "Note")]))?;
+
+        let translated_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/tpl").json_body(json!({
+                "data": {},
+                "lang": "fr",
+                "translations": { "fr": { "Invoice": "Facture" } }
+            }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "foo.pdf" }
+            }));
+        });
+
+        let overridden_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/tpl").json_body(json!({
+                "data": {},
+                "lang": "fr",
+                "translations": { "fr": { "Invoice": "Note" } }
+            }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "bar.pdf" }
+            }));
+        });
+
+        let untranslated_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/tpl").json_body(json!({
+                "data": {},
+                "lang": "en"
+            }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "baz.pdf" }
+            }));
+        });
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::bearer(api_token))
+            .translations(Arc::new(translations))
+            .build()?;
+
+        let template_id = TemplateId::new("tpl")?;
+
+        let json_data = JsonData::new(r#"{ "data": {}, "lang": "fr" }"#.to_string())?;
+        let render_id = carbone
+            .render_data(template_id.clone(), json_data.clone())
+            .await?;
+
+        assert_eq!(render_id.as_str(), "foo.pdf");
+        translated_mock.assert();
+
+        let options = CallOptions::new().translations(Arc::new(other_translations));
+        let render_id = carbone
+            .render_data_with(template_id.clone(), json_data, &options)
+            .await?;
+
+        assert_eq!(render_id.as_str(), "bar.pdf");
+        overridden_mock.assert();
+
+        // no catalog for the lang of the template
+        let json_data = JsonData::new(r#"{ "data": {}, "lang": "en" }"#.to_string())?;
+        let render_id = carbone.render_data(template_id, json_data).await?;
+
+        assert_eq!(render_id.as_str(), "baz.pdf");
+        untranslated_mock.assert();
+
+        Ok(())
+    }
+}
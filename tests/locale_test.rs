@@ -0,0 +1,269 @@
+use std::str::FromStr;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::config::{Config, ConfigLoader, PartialConfig};
+use carbone_sdk_rs::errors::{CarboneError, ConfigError};
+use carbone_sdk_rs::locale::*;
+use carbone_sdk_rs::options::CallOptions;
+use carbone_sdk_rs::template::TemplateId;
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::{parse_body, Helper};
+
+fn is_invalid<T>(result: Result<T, CarboneError>, field: &str) -> bool {
+    matches!(
+        result,
+        Err(CarboneError::ConfigError(ConfigError::InvalidValue(f, _))) if f == field
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lang() -> Result<(), CarboneError> {
+        assert_eq!(Lang::new("fr-FR")?.as_str(), "fr-fr");
+
+        for lang in [
+            "en",
+            "en-us",
+            "zh-Hant-TW",
+            "es-419",
+            "de-CH-1901",
+            "sl-rozaj-biske",
+            "en-US-u-ca-gregory",
+            "zh-yue-HK",
+            "en-x-private",
+            "x-whatever",
+        ] {
+            assert!(Lang::new(lang).is_ok(), "{}", lang);
+        }
+
+        for lang in [
+            "",
+            "e",
+            "fr_FR",
+            "english-",
+            "en--us",
+            "123",
+            "en-u",
+            "toolongtag",
+            "en-x",
+        ] {
+            assert!(is_invalid(Lang::new(lang), "lang"), "{}", lang);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_timezone() -> Result<(), CarboneError> {
+        assert_eq!(Timezone::new("Europe/Paris")?.as_str(), "Europe/Paris");
+        assert_eq!(
+            Timezone::from_str("America/Argentina/Buenos_Aires")?.to_string(),
+            "America/Argentina/Buenos_Aires"
+        );
+        assert!(Timezone::new("UTC").is_ok());
+        assert!(Timezone::new("Etc/GMT+5").is_ok());
+
+        assert!(is_invalid(Timezone::new("Europe/Atlantis"), "timezone"));
+        assert!(is_invalid(Timezone::new("CEST+2"), "timezone"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_currency() -> Result<(), CarboneError> {
+        assert_eq!(Currency::new("eur")?.as_str(), "EUR");
+        assert!(Currency::new("CHF").is_ok());
+
+        assert!(is_invalid(Currency::new("EURO"), "currency"));
+        assert!(is_invalid(Currency::new("ABC"), "currency"));
+
+        let rates = CurrencyRates::new()
+            .rate(Currency::new("EUR")?, 1.0)?
+            .rate(Currency::new("USD")?, 1.14)?;
+
+        assert_eq!(rates.get(&Currency::new("USD")?), Some(1.14));
+        assert!(is_invalid(
+            CurrencyRates::new().rate(Currency::new("USD")?, -1.0),
+            "currencyRates"
+        ));
+        assert!(is_invalid(
+            CurrencyRates::new().rate(Currency::new("USD")?, f64::NAN),
+            "currencyRates"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_settings_apply() -> Result<(), CarboneError> {
+        let defaults = RenderSettings {
+            lang: Some(Lang::new("fr-fr")?),
+            timezone: Some(Timezone::new("Europe/Paris")?),
+            currency_source: Some(Currency::new("EUR")?),
+            currency_rates: Some(CurrencyRates::new().rate(Currency::new("USD")?, 1.14)?),
+            ..Default::default()
+        };
+
+        let settings = RenderSettings {
+            lang: Some(Lang::new("de-de")?),
+            currency_target: Some(Currency::new("USD")?),
+            ..Default::default()
+        }
+        .or(&defaults);
+
+        // the settings of the body take precedence
+        let json_data = JsonData::new(r#"{ "data": {}, "timezone": "Asia/Tokyo" }"#.to_string())?;
+        let body = parse_body(&settings.apply(&json_data)?);
+
+        assert_eq!(
+            body,
+            json!({
+                "data": {},
+                "lang": "de-de",
+                "timezone": "Asia/Tokyo",
+                "currencySource": "EUR",
+                "currencyTarget": "USD",
+                "currencyRates": { "USD": 1.14 }
+            })
+        );
+
+        // nothing to add
+        let json_data = JsonData::new("not even json".to_string())?;
+        assert_eq!(RenderSettings::default().apply(&json_data)?, json_data);
+
+        assert!(matches!(
+            settings.apply(&json_data),
+            Err(CarboneError::RequestBodyNotWellFormedJsonError)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_render_defaults() -> Result<(), CarboneError> {
+        let config = Config::from_str(
+            r#"{
+                "apiUrl": "http://127.0.0.1",
+                "apiTimeout": 4,
+                "apiVersion": "4",
+                "renderDefaults": {
+                    "lang": "en-GB",
+                    "timezone": "Europe/London",
+                    "currencyRates": { "EUR": 1, "GBP": 0.85 }
+                }
+            }"#,
+        );
+
+        let config = config.map_err(CarboneError::from)?;
+
+        assert_eq!(config.render_defaults.lang, Some(Lang::new("en-gb")?));
+        assert_eq!(
+            config.render_defaults.timezone,
+            Some(Timezone::new("Europe/London")?)
+        );
+
+        let result = Config::from_str(
+            r#"{
+                "apiUrl": "http://127.0.0.1",
+                "apiTimeout": 4,
+                "apiVersion": "4",
+                "renderDefaults": { "currencySource": "EURO" }
+            }"#,
+        );
+
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+
+        // the sources are merged setting by setting
+        let config = ConfigLoader::new()
+            .overrides(PartialConfig {
+                render_defaults: Some(RenderSettings {
+                    currency_target: Some(Currency::new("CAD")?),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .load()?;
+
+        assert_eq!(
+            config.render_defaults.currency_target,
+            Some(Currency::new("CAD")?)
+        );
+        assert_eq!(config.render_defaults.lang, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_with_defaults() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let mut config = helper.create_config_for_mock_server(Some(&server))?;
+        config.render_defaults = RenderSettings {
+            lang: Some(Lang::new("fr-fr")?),
+            timezone: Some(Timezone::new("Europe/Paris")?),
+            ..Default::default()
+        };
+
+        let template_id = TemplateId::new("foo")?;
+
+        let defaults_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/foo").json_body(json!({
+                "data": {},
+                "lang": "fr-fr",
+                "timezone": "Europe/Paris"
+            }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "bar.pdf" }
+            }));
+        });
+
+        let override_mock = server.mock(|when, then| {
+            when.method("POST").path("/render/foo").json_body(json!({
+                "data": {},
+                "lang": "de-de",
+                "timezone": "Europe/Paris"
+            }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "baz.pdf" }
+            }));
+        });
+
+        let api_token = helper.create_api_token()?;
+        let carbone = Carbone::new(&config, &api_token)?;
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+
+        let render_id = carbone
+            .render_data(template_id.clone(), json_data.clone())
+            .await?;
+
+        assert_eq!(render_id.as_str(), "bar.pdf");
+        defaults_mock.assert();
+
+        let options = CallOptions::new().render_settings(RenderSettings {
+            lang: Some(Lang::new("de-de")?),
+            ..Default::default()
+        });
+
+        let render_id = carbone
+            .render_data_with(template_id, json_data, &options)
+            .await?;
+
+        assert_eq!(render_id.as_str(), "baz.pdf");
+        override_mock.assert();
+
+        Ok(())
+    }
+}
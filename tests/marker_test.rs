@@ -0,0 +1,173 @@
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::marker::*;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    fn location() -> MarkerLocation {
+        MarkerLocation {
+            entry: "".to_string(),
+            offset: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_marker() {
+        let marker = Marker::parse("d.products[i].priceUnit:formatN(2)", location()).unwrap();
+
+        assert_eq!(marker.root, MarkerRoot::Data);
+        assert_eq!(
+            marker.path,
+            vec![
+                PathSegment::Field("products".to_string()),
+                PathSegment::Array(ArrayAccess::Iterator {
+                    name: "i".to_string(),
+                    step: 0
+                }),
+                PathSegment::Field("priceUnit".to_string()),
+            ]
+        );
+        assert_eq!(marker.formatters[0].name, "formatN");
+        assert_eq!(marker.formatters[0].args, vec!["2".to_string()]);
+        assert_eq!(marker.value_type(), ValueType::Number);
+    }
+
+    #[test]
+    fn test_marker_loops_and_conditions() {
+        let marker = Marker::parse("d.orders[i].items[j+1].name", location()).unwrap();
+
+        assert_eq!(marker.loops(), vec!["d.orders[i]", "d.orders[i].items[j]"]);
+        assert!(!marker.is_condition());
+        assert_eq!(marker.formatters_str(), "");
+
+        let marker = Marker::parse("d.status:ifEQ(paid):show('Paid')", location()).unwrap();
+
+        assert!(marker.loops().is_empty());
+        assert!(marker.is_condition());
+        assert_eq!(marker.formatters_str(), "ifEQ(paid):show('Paid')");
+    }
+
+    #[test]
+    fn test_parse_marker_loop_end_and_filter() {
+        let marker = Marker::parse("d.products[i+1].name", location()).unwrap();
+        assert_eq!(marker.path_str(), "d.products[i+1].name");
+
+        let marker =
+            Marker::parse("d.items[type='a:b'].value:ifEQ(1):show('x')", location()).unwrap();
+        assert_eq!(
+            marker.path[1],
+            PathSegment::Array(ArrayAccess::Filter("type='a:b'".to_string()))
+        );
+        assert_eq!(marker.formatters.len(), 2);
+        assert_eq!(marker.value_type(), ValueType::Any);
+    }
+
+    #[test]
+    fn test_parse_not_a_data_marker() {
+        assert!(Marker::parse("t(Hello)", location()).is_none());
+        assert!(Marker::parse("#alias = d.list", location()).is_none());
+        assert!(Marker::parse(" color: red ", location()).is_none());
+    }
+
+    #[test]
+    fn test_parse_markers_odt() -> Result<(), CarboneError> {
+        let content = std::fs::read("tests/data/template.odt")?;
+        let markers = parse_markers(&content)?;
+
+        let paths: Vec<String> = markers.iter().map(|m| m.path_str()).collect();
+
+        assert!(paths.contains(&"d.company.name".to_string()));
+        assert!(paths.contains(&"d.products[i].priceUnit".to_string()));
+        assert!(markers.iter().all(|m| m.location.entry == "content.xml"));
+
+        let date = markers.iter().find(|m| m.path_str() == "d.date").unwrap();
+        assert_eq!(date.formatters[0].name, "convDate");
+        assert_eq!(date.value_type(), ValueType::Number);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_markers_txt() -> Result<(), CarboneError> {
+        let markers = parse_markers(b"Hello {d.firstname} {c.now:formatD('L')}!")?;
+
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[0].location.offset, 6);
+        assert_eq!(markers[1].root, MarkerRoot::Complement);
+        assert_eq!(markers[1].value_type(), ValueType::Date);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_marker_tree_json_schema() -> Result<(), CarboneError> {
+        let markers =
+            parse_markers(b"{d.name:upperCase()} {d.lines[i].qty:formatN()} {d.lines[i+1].qty}")?;
+        let schema = MarkerTree::from_markers(&markers).json_schema();
+
+        let expected = json!({
+            "$schema": JSON_SCHEMA_DRAFT,
+            "type": "object",
+            "properties": {
+                "data": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "lines": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "qty": { "type": "number" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(schema, expected);
+
+        let markers = parse_markers(b"{d.due:formatD('L')}")?;
+        let schema = MarkerTree::from_markers(&markers).json_schema();
+
+        assert_eq!(
+            schema["properties"]["data"]["properties"]["due"],
+            json!({
+                "type": "string",
+                "anyOf": [{ "format": "date" }, { "format": "date-time" }]
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_marker_tree_json_schema_order() -> Result<(), CarboneError> {
+        let first = parse_markers(b"{d.lines.total} {d.lines[i].qty:formatN()}")?;
+        let second = parse_markers(b"{d.lines[i].qty:formatN()} {d.lines.total}")?;
+
+        let schema = MarkerTree::from_markers(&first).json_schema();
+
+        assert_eq!(schema, MarkerTree::from_markers(&second).json_schema());
+        assert_eq!(
+            schema["properties"]["data"]["properties"]["lines"],
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "qty": { "type": "number" },
+                        "total": { "type": ["string", "number", "boolean", "null"] }
+                    }
+                }
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use httpmock::prelude::*;
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use serde_json::json;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::instrumentation::*;
+use carbone_sdk_rs::template::TemplateId;
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // the recorder is global, a single test records the metrics
+    #[tokio::test]
+    async fn test_metrics_recorded() -> Result<(), CarboneError> {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        recorder.install().unwrap();
+
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let render_id_value = "MTAuMjAuMjEuNDAgICAgBY4OM11wQg11ekv6_R0n0wcmVwb3J0.pdf";
+
+        server.mock(|when, then| {
+            when.method("POST").path("/render/foo");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": render_id_value }
+            }));
+        });
+
+        server.mock(|when, then| {
+            when.method("GET")
+                .path(format!("/render/{}", render_id_value));
+            then.status(200).body("report");
+        });
+
+        server.mock(|when, then| {
+            when.method("GET").path("/template/foo");
+            then.status(200).body("template");
+        });
+
+        server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(404)
+                .json_body(json!({ "success": false, "error": "not found" }));
+        });
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let json_data = JsonData::new(r#"{ "data": {}, "convertTo": "PDF" }"#.to_string())?;
+
+        let render_id = carbone
+            .render_data(TemplateId::new("foo")?, json_data)
+            .await?;
+        carbone.get_report(&render_id).await?;
+        carbone.download_template(&TemplateId::new("foo")?).await?;
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+        assert!(result.is_err());
+
+        let snapshot = snapshotter.snapshot().into_vec();
+
+        let value = |name: &str, labels: &[(&str, &str)]| {
+            snapshot.iter().find_map(|(key, _, _, value)| {
+                let key = key.key();
+                let matches = key.name() == name
+                    && labels.iter().all(|(label, expected)| {
+                        key.labels()
+                            .any(|l| l.key() == *label && l.value() == *expected)
+                    });
+                matches.then_some(value)
+            })
+        };
+
+        let render = [
+            ("operation", "render"),
+            ("status", "2xx"),
+            ("format", "pdf"),
+            ("error", "none"),
+        ];
+        assert_eq!(
+            value(METRIC_REQUESTS, &render),
+            Some(&DebugValue::Counter(1))
+        );
+
+        let get_report = [("operation", "get_report"), ("format", "pdf")];
+        assert_eq!(
+            value(METRIC_BYTES_RECEIVED, &get_report),
+            Some(&DebugValue::Counter("report".len() as u64))
+        );
+
+        let download_template = [("operation", "download_template")];
+        assert_eq!(
+            value(METRIC_BYTES_RECEIVED, &download_template),
+            Some(&DebugValue::Counter("template".len() as u64))
+        );
+
+        // only the bodies of the reports and templates are counted
+        assert_eq!(
+            value(METRIC_BYTES_RECEIVED, &[("operation", "render")]),
+            None
+        );
+
+        let delete = [
+            ("operation", "delete_template"),
+            ("status", "4xx"),
+            ("error", "http_status"),
+        ];
+        assert_eq!(
+            value(METRIC_REQUESTS, &delete),
+            Some(&DebugValue::Counter(1))
+        );
+
+        assert!(matches!(
+            value(METRIC_DURATION, &[("operation", "render")]),
+            Some(DebugValue::Histogram(values)) if values.len() == 1
+        ));
+
+        // the render id is never a label value
+        let render_id_label = snapshot.iter().any(|(key, _, _, _)| {
+            key.key()
+                .labels()
+                .any(|l| l.value().contains(render_id.as_str()))
+        });
+        assert!(!render_id_label);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::{Arc, Mutex};
+
+use httpmock::prelude::*;
+use reqwest::header::HeaderValue;
+use reqwest::{Request, StatusCode};
+use serde_json::json;
+
+use carbone_sdk_rs::auth::Auth;
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::middleware::{Middleware, ResponseMeta};
+use carbone_sdk_rs::template::TemplateId;
+
+mod helper;
+
+use helper::Helper;
+
+#[derive(Debug, Default)]
+struct Recorder {
+    bodies: Mutex<Vec<String>>,
+    statuses: Mutex<Vec<StatusCode>>,
+}
+
+impl Middleware for Recorder {
+    fn on_request(&self, request: &mut Request) -> Result<(), CarboneError> {
+        request
+            .headers_mut()
+            .insert("x-gateway-signature", HeaderValue::from_static("signed"));
+
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            let body = String::from_utf8_lossy(body).into_owned();
+            self.bodies.lock().unwrap().push(body);
+        }
+
+        Ok(())
+    }
+
+    fn on_response(&self, response: &ResponseMeta) {
+        self.statuses.lock().unwrap().push(response.status);
+    }
+}
+
+#[derive(Debug)]
+struct Deny;
+
+impl Middleware for Deny {
+    fn on_request(&self, _request: &mut Request) -> Result<(), CarboneError> {
+        Err(CarboneError::Error("denied".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_middleware_sees_requests_and_responses() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE")
+                .header("x-gateway-signature", "signed")
+                .header_exists("Authorization");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let recorder = Arc::new(Recorder::default());
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::bearer(api_token))
+            .middleware(recorder.clone())
+            .build()?;
+
+        carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        mock_server.assert();
+
+        assert_eq!(*recorder.statuses.lock().unwrap(), vec![StatusCode::OK]);
+        assert!(recorder.bodies.lock().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_middleware_error_aborts_the_request() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let recorder = Arc::new(Recorder::default());
+
+        let carbone = Carbone::builder(&config)
+            .middleware(Arc::new(Deny))
+            .middleware(recorder.clone())
+            .build()?;
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+
+        assert!(matches!(result, Err(CarboneError::Error(e)) if e == "denied"));
+        assert!(recorder.statuses.lock().unwrap().is_empty());
+        mock_server.assert_hits(0);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_middleware() -> Result<(), CarboneError> {
+        use carbone_sdk_rs::types::JsonData;
+
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST")
+                .path("/render/foo")
+                .header("x-gateway-signature", "signed");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "MTAuMjAuMjEuNDAgICAgBY4OM11wQg11ekv6_R0n0wcmVwb3J0.pdf" }
+            }));
+        });
+
+        let recorder = Arc::new(Recorder::default());
+
+        let carbone = carbone_sdk_rs::blocking::Carbone::builder(&config)
+            .middleware(recorder.clone())
+            .build()?;
+
+        let json_data = r#"{ "data": {}, "convertTo": "pdf" }"#;
+
+        carbone.render_data(
+            TemplateId::new("foo")?,
+            JsonData::new(json_data.to_string())?,
+        )?;
+
+        mock_server.assert();
+
+        assert_eq!(
+            *recorder.bodies.lock().unwrap(),
+            vec![json_data.to_string()]
+        );
+        assert_eq!(*recorder.statuses.lock().unwrap(), vec![StatusCode::OK]);
+
+        Ok(())
+    }
+}
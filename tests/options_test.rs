@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use reqwest::header::{HeaderName, HeaderValue};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::options::CallOptions;
+use carbone_sdk_rs::template::TemplateId;
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_call_timeout() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200)
+                .delay(Duration::from_secs(2))
+                .json_body(json!({ "success": true }));
+        });
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let options = CallOptions::new().timeout(Duration::from_millis(100));
+
+        let result = carbone
+            .delete_template_with(TemplateId::new("foo")?, &options)
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::RequestError(e)) if e.is_timeout()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_call_headers() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE")
+                .path("/template/foo")
+                .header("x-request-id", "42");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        let options = CallOptions::new().header(
+            HeaderName::from_static("x-request-id"),
+            HeaderValue::from_static("42"),
+        );
+
+        let is_deleted = carbone
+            .delete_template_with(TemplateId::new("foo")?, &options)
+            .await?;
+
+        // the default options send no extra header
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+
+        assert!(is_deleted);
+        assert!(result.is_err());
+        mock_server.assert_hits(1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_call_cancelled() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("POST").path("/render/foo");
+            then.status(200)
+                .delay(Duration::from_secs(5))
+                .json_body(json!({
+                    "success": true,
+                    "data": { "renderId": "MTAuMjAuMjEuNDAgICAgBY4OM11wQg11ekv6_R0n0wcmVwb3J0.pdf" }
+                }));
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        let cancellation_token = CancellationToken::new();
+        let options = CallOptions::new().cancellation_token(cancellation_token.clone());
+
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancellation_token.cancel();
+        };
+
+        let json_data = JsonData::new(r#"{ "data": {}, "convertTo": "pdf" }"#.to_string())?;
+
+        let (result, _) = tokio::join!(
+            carbone.generate_report_with_template_id_with(
+                TemplateId::new("foo")?,
+                json_data,
+                &options
+            ),
+            cancel
+        );
+
+        assert!(matches!(result, Err(CarboneError::Cancelled)));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_call_cancelled() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let carbone = carbone_sdk_rs::blocking::Carbone::builder(&config).build()?;
+
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+
+        let options = CallOptions::new().cancellation_token(cancellation_token);
+
+        let result = carbone.delete_template_with(TemplateId::new("foo")?, &options);
+
+        assert!(matches!(result, Err(CarboneError::Cancelled)));
+        mock_server.assert_hits(0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::auth::Auth;
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::rate_limit::{RateLimit, RateLimiter};
+use carbone_sdk_rs::template::TemplateId;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_wait_for_a_permit() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        // the render budget does not slow down the template requests
+        let rate_limiter = RateLimiter::new()
+            .render(RateLimit::per_minute(1))
+            .template(RateLimit::new(2, Duration::from_millis(200)));
+
+        let carbone = Carbone::builder(&config)
+            .auth(Auth::bearer(api_token))
+            .rate_limiter(Arc::new(rate_limiter))
+            .build()?;
+
+        let started_at = Instant::now();
+
+        for _ in 0..4 {
+            carbone.delete_template(TemplateId::new("foo")?).await?;
+        }
+
+        // 2 requests in a burst, then one every 100ms
+        assert!(started_at.elapsed() >= Duration::from_millis(190));
+        mock_server.assert_hits(4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers_adjust_the_budget() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200)
+                .header("X-RateLimit-Remaining", "0")
+                .header("X-RateLimit-Reset", "1")
+                .json_body(json!({ "success": true }));
+        });
+
+        let rate_limiter = RateLimiter::new().template(RateLimit::per_second(100));
+
+        let carbone = Carbone::builder(&config)
+            .rate_limiter(Arc::new(rate_limiter))
+            .build()?;
+
+        carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        let started_at = Instant::now();
+
+        carbone.delete_template(TemplateId::new("foo")?).await?;
+
+        assert!(started_at.elapsed() >= Duration::from_millis(900));
+        mock_server.assert_hits(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_too_many_requests_sent_again() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(429)
+                .header("Retry-After", "1")
+                .json_body(json!({ "success": false, "error": "Too Many Requests" }));
+        });
+
+        let rate_limiter = RateLimiter::new().template(RateLimit::per_second(100));
+
+        let carbone = Carbone::builder(&config)
+            .rate_limiter(Arc::new(rate_limiter))
+            .build()?;
+
+        let started_at = Instant::now();
+
+        let result = carbone.delete_template(TemplateId::new("foo")?).await;
+
+        assert!(started_at.elapsed() >= Duration::from_millis(1900));
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+        mock_server.assert_hits(3);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_requests_wait_for_a_permit() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("DELETE");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let rate_limiter =
+            RateLimiter::new().template(RateLimit::new(1, Duration::from_millis(100)));
+
+        let carbone = carbone_sdk_rs::blocking::Carbone::builder(&config)
+            .rate_limiter(Arc::new(rate_limiter))
+            .build()?;
+
+        let started_at = Instant::now();
+
+        for _ in 0..3 {
+            carbone.delete_template(TemplateId::new("foo")?)?;
+        }
+
+        assert!(started_at.elapsed() >= Duration::from_millis(190));
+        mock_server.assert_hits(3);
+
+        Ok(())
+    }
+}
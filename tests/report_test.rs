@@ -0,0 +1,368 @@
+use std::fs;
+use std::io::{Cursor, Read};
+
+use bytes::Bytes;
+use httpmock::prelude::*;
+use serde_json::json;
+use zip::ZipArchive;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::render::RenderId;
+use carbone_sdk_rs::report::*;
+use carbone_sdk_rs::template::TemplateId;
+use carbone_sdk_rs::types::JsonData;
+
+mod helper;
+
+use helper::{Helper, TempDir};
+
+fn report(render_id: &str, file_name: Option<&str>, content: &'static str) -> Report {
+    Report {
+        render_id: RenderId::new(render_id).unwrap(),
+        template_id: None,
+        data_hash: None,
+        file_name: file_name.map(String::from),
+        content_type: None,
+        content: Bytes::from_static(content.as_bytes()),
+    }
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_report_name() {
+        assert_eq!(report("foo.pdf", None, "").name(), "foo.pdf");
+        assert_eq!(
+            report("foo.pdf", Some("invoice.pdf"), "").name(),
+            "invoice.pdf"
+        );
+        assert_eq!(
+            report("foo.pdf", Some("../../etc/passwd"), "").name(),
+            "passwd.pdf"
+        );
+        assert_eq!(report("foo.pdf", Some("..\\"), "").name(), "foo.pdf");
+        assert_eq!(
+            report("foo.pdf", Some("bad\u{0}name.pdf"), "").name(),
+            "badname.pdf"
+        );
+        assert_eq!(report("foo.pdf", Some("invoice"), "").name(), "invoice.pdf");
+        assert_eq!(
+            report("foo.PDF", Some("invoice.pdf"), "").name(),
+            "invoice.pdf"
+        );
+        assert_eq!(
+            report("foo.pdf", Some("invoice.2024"), "").name(),
+            "invoice.2024.pdf"
+        );
+
+        // the render id is not trusted either
+        assert_eq!(report("../../x.pdf", None, "").name(), "x.pdf");
+        assert_eq!(report("../../x", None, "").name(), "x");
+        assert_eq!(
+            report("foo.pdf/../../x", Some("invoice"), "").name(),
+            "invoice"
+        );
+        assert_eq!(
+            report("..", None, "").name(),
+            "5ec1f7e700f37c3d0b2981d04855fc34b94aaa15457b05ca571817442d228f81"
+        );
+    }
+
+    #[test]
+    fn test_report_save_to() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("save_to");
+        let dir = temp_dir.path();
+
+        let path = report("a.pdf", Some("../invoice"), "first").save_to(dir)?;
+
+        assert_eq!(path, dir.join("invoice.pdf"));
+        assert_eq!(fs::read_to_string(&path)?, "first");
+
+        let path = report("b.pdf", Some("invoice.pdf"), "second").save_to(dir)?;
+
+        assert_eq!(path, dir.join("invoice.pdf"));
+        assert_eq!(fs::read_to_string(&path)?, "second");
+
+        let result =
+            report("c.pdf", Some("invoice.pdf"), "third").save_to_with(dir, OnConflict::Fail);
+
+        assert!(matches!(result, Err(CarboneError::FileAlreadyExists(_))));
+        assert_eq!(fs::read_to_string(&path)?, "second");
+
+        let path =
+            report("d.pdf", Some("invoice.pdf"), "fourth").save_to_with(dir, OnConflict::Rename)?;
+
+        assert_eq!(path, dir.join("invoice-2.pdf"));
+        assert_eq!(fs::read_to_string(&path)?, "fourth");
+
+        // no temporary file is left behind
+        let mut names: Vec<String> = fs::read_dir(dir)?
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["invoice-2.pdf", "invoice.pdf"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_save_to_render_id_with_directories() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("save_to_render_id");
+        let dir = temp_dir.path();
+
+        let path = report("../../escaped.pdf", None, "content").save_to(dir.join("reports"))?;
+
+        assert_eq!(path, dir.join("reports").join("escaped.pdf"));
+        assert!(!dir.join("escaped.pdf").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_archive() -> Result<(), CarboneError> {
+        let mut archive = ReportArchive::new(Cursor::new(Vec::new()));
+
+        let mut first = report("a.pdf", Some("invoice.pdf"), "first");
+        first.template_id = Some(TemplateId::new("tpl")?);
+        first.data_hash = Some("abc".to_string());
+
+        assert_eq!(archive.add(&first)?, "invoice.pdf");
+        assert_eq!(
+            archive.add(&report("b.pdf", Some("Invoice.pdf"), "second"))?,
+            "Invoice-2.pdf"
+        );
+        assert_eq!(
+            archive.add(&report("c.pdf", Some("invoice.pdf"), "third"))?,
+            "invoice-3.pdf"
+        );
+        assert_eq!(
+            archive.add(&report("d.json", Some("manifest.json"), "{}"))?,
+            "manifest-2.json"
+        );
+        assert_eq!(archive.add(&report("e.pdf", None, "fifth"))?, "e.pdf");
+
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+        archive.add_failure(
+            Some(&TemplateId::new("tpl")?),
+            Some(&json_data),
+            &CarboneError::Error("render failed".to_string()),
+        );
+
+        let content = archive.finish()?.into_inner();
+        let mut archive = ZipArchive::new(Cursor::new(content)).unwrap();
+
+        let names: Vec<String> = (0..archive.len())
+            .map(|index| archive.by_index(index).unwrap().name().to_string())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "invoice.pdf",
+                "Invoice-2.pdf",
+                "invoice-3.pdf",
+                "manifest-2.json",
+                "e.pdf",
+                MANIFEST_NAME
+            ]
+        );
+        assert_eq!(read_entry(&mut archive, "Invoice-2.pdf"), "second");
+
+        let manifest: Manifest =
+            serde_json::from_str(&read_entry(&mut archive, MANIFEST_NAME)).unwrap();
+
+        assert_eq!(manifest.reports.len(), 5);
+        assert_eq!(
+            manifest.reports[0],
+            ManifestReport {
+                name: "invoice.pdf".to_string(),
+                render_id: "a.pdf".to_string(),
+                template_id: Some("tpl".to_string()),
+                data_hash: Some("abc".to_string()),
+                output_hash: first.hash(),
+                size: 5,
+            }
+        );
+        assert_eq!(
+            manifest.failures,
+            vec![ManifestFailure {
+                template_id: Some("tpl".to_string()),
+                data_hash: Some(data_hash(&json_data)),
+                error: CarboneError::Error("render failed".to_string()).to_string(),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_report() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        server.mock(|when, then| {
+            when.method("GET").path("/render/foo.pdf");
+            then.status(200)
+                .header("Content-Type", "application/pdf")
+                .header(
+                    "Content-Disposition",
+                    "attachment; filename=\"facture.pdf\"; filename*=UTF-8''facture%20%C3%A9t%C3%A9.pdf",
+                )
+                .body("%PDF");
+        });
+
+        server.mock(|when, then| {
+            when.method("GET").path("/render/bar.pdf");
+            then.status(200)
+                .header(
+                    "Content-Disposition",
+                    "attachment; filename=\"a \\\"b\\\".pdf\"",
+                )
+                .body("%PDF");
+        });
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let report = carbone.fetch_report(&RenderId::new("foo.pdf")?).await?;
+
+        assert_eq!(report.file_name.as_deref(), Some("facture été.pdf"));
+        assert_eq!(report.content_type.as_deref(), Some("application/pdf"));
+        assert_eq!(report.content, Bytes::from_static(b"%PDF"));
+
+        let report = carbone.fetch_report(&RenderId::new("bar.pdf")?).await?;
+
+        assert_eq!(report.name(), "a \"b\".pdf");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_report_to_file() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+        let temp_dir = TempDir::new("get_report_to_file");
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir)?;
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("GET").path("/render/foo.pdf");
+            then.status(200)
+                .header(
+                    "Content-Disposition",
+                    "attachment; filename=\"../../report\"",
+                )
+                .body("%PDF");
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+        let render_id = RenderId::new("foo.pdf")?;
+
+        let path = carbone.get_report_to_file(&render_id, dir).await?;
+
+        assert_eq!(path, dir.join("report.pdf"));
+        assert_eq!(fs::read_to_string(&path)?, "%PDF");
+
+        let path = carbone
+            .get_report_to_file(&render_id, dir.join("custom.bin"))
+            .await?;
+
+        assert_eq!(path, dir.join("custom.bin"));
+
+        let result = carbone
+            .get_report_to_file_with(&render_id, &path, OnConflict::Fail, &Default::default())
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::FileAlreadyExists(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_report() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("POST").path("/render/tpl");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "foo.pdf" }
+            }));
+        });
+
+        server.mock(|when, then| {
+            when.method("GET").path("/render/foo.pdf");
+            then.status(200).body("%PDF");
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+        let report = carbone
+            .render_report(TemplateId::new("tpl")?, json_data.clone())
+            .await?;
+
+        assert_eq!(report.render_id.as_str(), "foo.pdf");
+        assert_eq!(report.template_id, Some(TemplateId::new("tpl")?));
+        assert_eq!(report.data_hash, Some(data_hash(&json_data)));
+        assert_eq!(report.name(), "foo.pdf");
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_render_report() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("POST").path("/render/tpl");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "foo.pdf" }
+            }));
+        });
+
+        server.mock(|when, then| {
+            when.method("GET").path("/render/foo.pdf");
+            then.status(200)
+                .header("Content-Disposition", "attachment; filename=invoice.pdf")
+                .body("%PDF");
+        });
+
+        let carbone = carbone_sdk_rs::blocking::Carbone::builder(&config).build()?;
+
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+        let report = carbone.render_report(TemplateId::new("tpl")?, json_data)?;
+
+        assert_eq!(report.name(), "invoice.pdf");
+        assert_eq!(report.template_id, Some(TemplateId::new("tpl")?));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::marker::{parse_markers, MarkerTree};
+use carbone_sdk_rs::sample::*;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use carbone_sdk_rs::template::TemplateFile;
+    use serde_json::Value;
+
+    fn tree(template: &[u8]) -> Result<MarkerTree, CarboneError> {
+        Ok(MarkerTree::from_markers(&parse_markers(template)?))
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+        let tree = template_file.marker_tree()?;
+
+        let first = SampleDataGenerator::new(7).generate(&tree)?;
+        let second = SampleDataGenerator::new(7).generate(&tree)?;
+        let other = SampleDataGenerator::new(8).generate(&tree)?;
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_value_types() -> Result<(), CarboneError> {
+        let tree = tree(
+            b"{d.customer.firstname} {d.lines[i].price:formatN(2)} {d.lines[i].at:formatD(LL)} {c.now}",
+        )?;
+
+        let mut generator = SampleDataGenerator::new(1);
+        generator.array_len = 5;
+        generator.convert_to = None;
+
+        let json_data = generator.generate(&tree)?;
+        let value: Value = serde_json::from_str(json_data.as_str()).unwrap();
+
+        let lines = value["data"]["lines"].as_array().unwrap();
+
+        assert!(value["data"]["customer"]["firstname"].is_string());
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|line| line["price"].is_number()));
+        assert!(lines
+            .iter()
+            .all(|line| line["at"].as_str().unwrap().ends_with('Z')));
+        assert!(value["complement"]["now"].is_string());
+        assert!(value.get("convertTo").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_default_convert_to() -> Result<(), CarboneError> {
+        let json_data = SampleDataGenerator::new(1).generate(&tree(b"{d.name}")?)?;
+        let value: Value = serde_json::from_str(json_data.as_str()).unwrap();
+
+        assert_eq!(value["convertTo"], "pdf");
+        assert!(value.get("complement").is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::io::{Cursor, Write};
+
+use httpmock::prelude::*;
+use serde_json::json;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::sniff::*;
+
+mod helper;
+
+use helper::Helper;
+
+fn zip(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for (name, content) in entries {
+        writer.start_file(*name, FileOptions::default()).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+
+    writer.finish().unwrap().into_inner()
+}
+
+// overwrite the uncompressed size declared for the entries of an archive
+fn set_declared_size(content: &mut [u8], size: u32) {
+    for (signature, offset) in [(b"PK\x03\x04", 22), (b"PK\x01\x02", 24)] {
+        for start in 0..content.len() - 4 {
+            if &content[start..start + 4] == signature {
+                content[start + offset..start + offset + 4].copy_from_slice(&size.to_le_bytes());
+            }
+        }
+    }
+}
+
+fn is_invalid(result: Result<TemplateFormat, CarboneError>, reason: &str) -> bool {
+    matches!(result, Err(CarboneError::InvalidTemplate(e)) if e.contains(reason))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_sniff_odf() -> Result<(), CarboneError> {
+        let content = fs::read("tests/data/template.odt")?;
+        assert_eq!(TemplateFormat::sniff(&content)?, TemplateFormat::Odt);
+
+        let content = zip(&[
+            ("mimetype", "application/vnd.oasis.opendocument.spreadsheet"),
+            ("content.xml", "<office:document-content/>"),
+        ]);
+        let format = TemplateFormat::sniff(&content)?;
+
+        assert_eq!(format, TemplateFormat::Ods);
+        assert_eq!(format.extension(), "ods");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_ooxml() -> Result<(), CarboneError> {
+        let content = zip(&[
+            ("[Content_Types].xml", "<Types/>"),
+            ("word/document.xml", "<w:document/>"),
+        ]);
+        assert_eq!(TemplateFormat::sniff(&content)?, TemplateFormat::Docx);
+
+        let content = zip(&[
+            ("[Content_Types].xml", "<Types/>"),
+            ("xl/workbook.xml", "<workbook/>"),
+        ]);
+        let format = TemplateFormat::sniff(&content)?;
+
+        assert_eq!(format, TemplateFormat::Xlsx);
+        assert_eq!(
+            format.mime_type(),
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_text() -> Result<(), CarboneError> {
+        let content = fs::read("tests/data/template.test.html")?;
+        assert_eq!(TemplateFormat::sniff(&content)?, TemplateFormat::Html);
+
+        let content = fs::read("tests/data/template.test.txt")?;
+        assert_eq!(TemplateFormat::sniff(&content)?, TemplateFormat::Text);
+
+        let content = b"\xEF\xBB\xBF<?xml version=\"1.0\"?><invoice>{d.id}</invoice>";
+        assert_eq!(TemplateFormat::sniff(content)?, TemplateFormat::Xml);
+
+        let content = b"<?xml version=\"1.0\"?><html xmlns=\"http://www.w3.org/1999/xhtml\"/>";
+        assert_eq!(TemplateFormat::sniff(content)?, TemplateFormat::Html);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_invalid() -> Result<(), CarboneError> {
+        assert!(is_invalid(TemplateFormat::sniff(b""), "empty"));
+
+        assert!(is_invalid(
+            TemplateFormat::sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            "binary"
+        ));
+
+        let content = zip(&[("readme.txt", "not a document")]);
+        assert!(is_invalid(
+            TemplateFormat::sniff(&content),
+            "neither an OpenDocument"
+        ));
+
+        let content = zip(&[("mimetype", "application/epub+zip")]);
+        assert!(is_invalid(TemplateFormat::sniff(&content), "unsupported"));
+
+        let oversized = vec![b'a'; MAX_TEMPLATE_SIZE + 1];
+        assert!(is_invalid(TemplateFormat::sniff(&oversized), "bytes"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_corrupt() -> Result<(), CarboneError> {
+        let content = fs::read("tests/data/template.odt")?;
+        let truncated = &content[..content.len() / 2];
+
+        assert!(is_invalid(TemplateFormat::sniff(truncated), "corrupt"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_zip_bomb() -> Result<(), CarboneError> {
+        let zeros = "\0".repeat(2 * 1024 * 1024);
+
+        let content = zip(&[("content.xml", &zeros)]);
+        assert!(is_invalid(
+            TemplateFormat::sniff(&content),
+            "compressed more than"
+        ));
+
+        let mut content = zip(&[("content.xml", "<document/>")]);
+        set_declared_size(&mut content, 300 * 1024 * 1024);
+        assert!(is_invalid(
+            TemplateFormat::sniff(&content),
+            "decompresses to more than"
+        ));
+
+        // the declared size is not trusted
+        let mut content = zip(&[("content.xml", &zeros)]);
+        set_declared_size(&mut content, 1024);
+        assert!(is_invalid(
+            TemplateFormat::sniff(&content),
+            "larger than declared"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sniff_password_protected() -> Result<(), CarboneError> {
+        let content = zip(&[
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            (
+                "META-INF/manifest.xml",
+                "<manifest:file-entry><manifest:encryption-data/></manifest:file-entry>",
+            ),
+        ]);
+        assert!(is_invalid(
+            TemplateFormat::sniff(&content),
+            "password-protected"
+        ));
+
+        // encrypted OOXML documents are OLE compound files
+        let content = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1\0\0\0\0";
+        assert!(is_invalid(
+            TemplateFormat::sniff(content),
+            "password-protected"
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_invalid_template_not_sent() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let result = carbone
+            .upload_template("template.odt", b"\0\0\0\0".to_vec(), None)
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::InvalidTemplate(_))));
+        mock_server.assert_hits(0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_template_sniffed_mime_type() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("Content-Type: application/vnd.oasis.opendocument.text");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": "foo" }
+            }));
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        // no extension to guess the type from
+        let content = fs::read("tests/data/template.odt")?;
+        let template_id = carbone.upload_template("template", content, None).await?;
+
+        mock_server.assert();
+        assert_eq!(template_id.as_str(), "foo");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rs::carbone::Carbone;
+use carbone_sdk_rs::config::Config;
+use carbone_sdk_rs::errors::CarboneError;
+use carbone_sdk_rs::status::{ServiceStatus, VersionCheck};
+use carbone_sdk_rs::types::ApiVersion;
+
+mod helper;
+
+use helper::Helper;
+
+fn service_status(version: Option<&str>) -> ServiceStatus {
+    ServiceStatus {
+        success: true,
+        code: Some(200),
+        message: Some("OK".to_string()),
+        version: version.map(str::to_string),
+        warning: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_service_status_api_version() -> Result<(), CarboneError> {
+        assert_eq!(service_status(Some("4.22.8")).api_version(), Some(4));
+        assert_eq!(service_status(Some("v5.0.0")).api_version(), Some(5));
+        assert_eq!(service_status(Some("beta")).api_version(), None);
+        assert_eq!(service_status(None).api_version(), None);
+
+        let api_version = ApiVersion::new("4".to_string())?;
+
+        assert_eq!(
+            service_status(Some("4.22.8")).supports(&api_version),
+            Some(true)
+        );
+        assert_eq!(
+            service_status(Some("5.0.0")).supports(&api_version),
+            Some(true)
+        );
+        assert_eq!(
+            service_status(Some("3.5.1")).supports(&api_version),
+            Some(false)
+        );
+        assert_eq!(service_status(None).supports(&api_version), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_status() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET")
+                .path("/status")
+                .header("carbone-version", "4");
+            then.status(200).json_body(json!({
+                "success": true,
+                "code": 200,
+                "message": "OK",
+                "version": "4.22.8"
+            }));
+        });
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let status = carbone.status().await?;
+
+        mock_server.assert();
+        assert!(status.is_healthy());
+        assert_eq!(status, service_status(Some("4.22.8")));
+        assert_eq!(carbone.server_api_version(), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_status_unexpected_response() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(502).body("Bad Gateway");
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        let result = carbone.status().await;
+
+        assert!(matches!(result, Err(CarboneError::ResponseError(e)) if e.contains("Bad Gateway")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_version() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200)
+                .json_body(json!({ "success": true, "version": "4.22.8" }));
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        carbone.negotiate_version(VersionCheck::Fail).await?;
+
+        assert_eq!(carbone.server_api_version(), Some(4));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_unsupported_version() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let config = Config::new(server.base_url(), 10, ApiVersion::new("5".to_string())?)?;
+
+        server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200)
+                .json_body(json!({ "success": true, "version": "4.22.8" }));
+        });
+
+        let carbone = Carbone::builder(&config).build()?;
+
+        let result = carbone.negotiate_version(VersionCheck::Fail).await;
+
+        assert!(matches!(
+            result,
+            Err(CarboneError::UnsupportedApiVersion { requested, server })
+                if requested == "5" && server == "4.22.8"
+        ));
+        assert_eq!(carbone.server_api_version(), Some(4));
+
+        let status = carbone.negotiate_version(VersionCheck::Warn).await?;
+
+        assert_eq!(status.api_version(), Some(4));
+        assert_eq!(
+            status.warning,
+            Some(
+                CarboneError::UnsupportedApiVersion {
+                    requested: "5".to_string(),
+                    server: "4.22.8".to_string(),
+                }
+                .to_string()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_status() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let server = MockServer::start();
+
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200)
+                .json_body(json!({ "success": true, "version": "4.22.8" }));
+        });
+
+        let carbone = carbone_sdk_rs::blocking::Carbone::builder(&config).build()?;
+
+        let status = carbone.negotiate_version(VersionCheck::Fail)?;
+
+        assert!(status.is_healthy());
+        assert_eq!(status.warning, None);
+        assert_eq!(carbone.server_api_version(), Some(4));
+
+        Ok(())
+    }
+}
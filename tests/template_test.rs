@@ -1,8 +1,22 @@
 use std::fs;
+use std::io::Cursor;
+
+use zip::{CompressionMethod, ZipArchive};
 
 use carbone_sdk_rs::errors::CarboneError;
 use carbone_sdk_rs::template::*;
 
+mod helper;
+
+use helper::TempDir;
+
+fn entry_names(content: &[u8]) -> Vec<String> {
+    let mut archive = ZipArchive::new(Cursor::new(content)).unwrap();
+    (0..archive.len())
+        .map(|index| archive.by_index(index).unwrap().name().to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -23,7 +37,7 @@ mod tests {
     #[test]
     fn test_template_file_file_content_given() -> Result<(), CarboneError> {
         let template_file_path = "tests/data/template.test.odt";
-        let file_content = fs::read(template_file_path.to_owned())?;
+        let file_content = fs::read(template_file_path)?;
         let template_file = TemplateFile::new(template_file_path.to_string(), Some(file_content))?;
 
         assert_eq!(template_file.path_as_str(), template_file_path);
@@ -140,4 +154,244 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_template_file_json_schema() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.test.odt".to_string(), None)?;
+        let schema = template_file.json_schema()?;
+
+        let data = &schema["properties"]["data"];
+
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(data["type"], "object");
+        assert!(data["properties"]["firstname"].is_object());
+        assert!(data["properties"]["lastname"].is_object());
+        assert!(schema["properties"]["complement"].is_null());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_from_bytes() -> Result<(), CarboneError> {
+        let content = include_bytes!("data/template.test.odt");
+        let template_file = TemplateFile::from_bytes("templates/invoice.odt", &content[..])?;
+
+        let expected_template_id = TemplateId::new(
+            "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string(),
+        )?;
+
+        assert!(template_file.is_in_memory());
+        assert!(template_file.metadata.is_none());
+        assert_eq!(template_file.name(), "invoice.odt");
+        assert_eq!(template_file.extension(), Some("odt"));
+        assert_eq!(template_file.generate_id(None)?, expected_template_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_from_reader() -> Result<(), CarboneError> {
+        let file = fs::File::open("tests/data/template.test.odt")?;
+        let template_file = TemplateFile::from_reader("invoice", file)?;
+
+        assert_eq!(template_file.name(), "invoice");
+        assert_eq!(template_file.extension(), None);
+        assert_eq!(
+            template_file.read_content()?,
+            fs::read("tests/data/template.test.odt")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_from_bytes_empty_name() -> Result<(), CarboneError> {
+        let result = TemplateFile::from_bytes("", b"Hello {d.name}".to_vec());
+
+        assert!(matches!(result, Err(CarboneError::EmptyString(name)) if name == "name"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_on_disk() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.test.odt".to_string(), None)?;
+
+        assert!(!template_file.is_in_memory());
+        assert_eq!(template_file.name(), "template.test.odt");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_unpacked_dir_round_trip() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("round_trip");
+        let dir = temp_dir.path().join("invoice");
+
+        let template_file = TemplateFile::new("tests/data/template.test.odt".to_string(), None)?;
+        template_file.unpack_to_dir(&dir)?;
+
+        assert!(dir.join("mimetype").is_file());
+        assert!(dir.join("content.xml").is_file());
+
+        let packed = TemplateFile::from_unpacked_dir(&dir)?;
+        let content = packed.read_content()?;
+
+        let mut archive = ZipArchive::new(Cursor::new(content.clone())).unwrap();
+        let mimetype = archive.by_index(0).unwrap();
+
+        assert_eq!(mimetype.name(), "mimetype");
+        assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+        drop(mimetype);
+
+        assert_eq!(packed.name(), "invoice.odt");
+        assert!(packed.is_in_memory());
+        assert_eq!(packed.markers()?, template_file.markers()?);
+
+        // rewriting the files and adding version control ones changes nothing
+        fs::write(dir.join("content.xml"), fs::read(dir.join("content.xml"))?)?;
+        fs::write(dir.join(".gitkeep"), "")?;
+
+        let repacked = TemplateFile::from_unpacked_dir(&dir)?;
+
+        assert_eq!(repacked.read_content()?, content);
+        assert_eq!(repacked.generate_id(None)?, packed.generate_id(None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_unpacked_ooxml_dir() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("ooxml");
+        let dir = temp_dir.path().join("report.docx");
+
+        fs::create_dir_all(dir.join("word/_rels"))?;
+        fs::create_dir_all(dir.join("_rels"))?;
+        fs::write(dir.join("_rels/.rels"), "<Relationships/>")?;
+        fs::write(dir.join(".DS_Store"), "")?;
+        fs::write(
+            dir.join("word/document.xml"),
+            "<w:document>{d.name}</w:document>",
+        )?;
+        fs::write(dir.join("word/_rels/document.xml.rels"), "<Relationships/>")?;
+        fs::write(dir.join("[Content_Types].xml"), "<Types/>")?;
+
+        let template_file = TemplateFile::from_unpacked_dir(&dir)?;
+
+        assert_eq!(template_file.name(), "report.docx");
+        assert_eq!(
+            entry_names(&template_file.read_content()?),
+            vec![
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "word/_rels/document.xml.rels",
+                "word/document.xml"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_unpacked_dir_invalid() -> Result<(), CarboneError> {
+        let result = TemplateFile::from_unpacked_dir("tests/data/unknown_dir");
+        assert!(matches!(result, Err(CarboneError::TemplateFileNotFound(_))));
+
+        let temp_dir = TempDir::new("invalid");
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("notes.txt"), "Hello {d.name}")?;
+
+        let result = TemplateFile::from_unpacked_dir(dir);
+        assert!(matches!(result, Err(CarboneError::InvalidTemplate(_))));
+
+        // a flat template can not be unpacked
+        let template_file = TemplateFile::new("tests/data/template.test.txt".to_string(), None)?;
+        let result = template_file.unpack_to_dir(dir.join("unpacked"));
+        assert!(matches!(result, Err(CarboneError::InvalidTemplate(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_id_canonical_mode() -> Result<(), CarboneError> {
+        let temp_dir = TempDir::new("canonical");
+        let dir = temp_dir.path().join("invoice");
+
+        let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+        template_file.unpack_to_dir(&dir)?;
+
+        // zipped again: other timestamps and compression
+        let repacked = TemplateFile::from_unpacked_dir(&dir)?;
+
+        assert_ne!(
+            repacked.generate_id(None)?,
+            template_file.generate_id(None)?
+        );
+
+        let canonical_id = template_file.generate_id_with_mode(None, TemplateIdMode::Canonical)?;
+
+        assert_eq!(
+            repacked.generate_id_with_mode(None, TemplateIdMode::Canonical)?,
+            canonical_id
+        );
+
+        // saved again: new date, editing cycles and line endings
+        let meta = fs::read_to_string(dir.join("meta.xml"))?
+            .replace(
+                "<dc:date>2019-09-16T19:59:01.533835102</dc:date>",
+                "<dc:date>2024-01-01T00:00:00</dc:date>",
+            )
+            .replace(
+                "<meta:editing-cycles>32</meta:editing-cycles>",
+                "<meta:editing-cycles>33</meta:editing-cycles>",
+            )
+            .replace('\n', "\r\n");
+        fs::write(dir.join("meta.xml"), meta)?;
+
+        let resaved = TemplateFile::from_unpacked_dir(&dir)?;
+
+        assert_eq!(
+            resaved.generate_id_with_mode(None, TemplateIdMode::Canonical)?,
+            canonical_id
+        );
+
+        // a payload still gives another id
+        assert_ne!(
+            resaved.generate_id_with_mode(Some("ThisIsAPayload"), TemplateIdMode::Canonical)?,
+            canonical_id
+        );
+
+        // while any change of the content does
+        let content = fs::read_to_string(dir.join("content.xml"))?.replace("{d.", "{d.x");
+        fs::write(dir.join("content.xml"), content)?;
+
+        let changed = TemplateFile::from_unpacked_dir(&dir)?;
+
+        assert_ne!(
+            changed.generate_id_with_mode(None, TemplateIdMode::Canonical)?,
+            canonical_id
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_id_canonical_mode_flat_template() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.test.html".to_string(), None)?;
+
+        assert_eq!(
+            template_file.generate_id_with_mode(None, TemplateIdMode::Canonical)?,
+            template_file.generate_id_with_mode(None, TemplateIdMode::Raw)?
+        );
+        assert_eq!(
+            template_file.generate_id_with_mode(None, TemplateIdMode::default())?,
+            template_file.generate_id(None)?
+        );
+
+        Ok(())
+    }
 }